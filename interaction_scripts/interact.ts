@@ -3,7 +3,6 @@ import * as web3 from "@solana/web3.js"
 import * as borsh from "@project-serum/borsh"
 import * as token from "@solana/spl-token"
 import { StudentIntro } from "./models/StudentIntro"
-import { StudentIntroCounter } from "./models/StudentIntroCounter"
 import { StudentIntroReply } from "./models/StudentIntroReply"
 import BN from "bn.js"
 
@@ -16,6 +15,13 @@ const introInstructionLayout = borsh.struct([
 const replyInstructionLayout = borsh.struct([
   borsh.u8("variant"),
   borsh.str("reply"),
+  borsh.u8("counterBump"),
+])
+const counterAccountLayout = borsh.struct([
+  borsh.array(borsh.u8(), 8, "discriminator"),
+  borsh.u8("version"),
+  borsh.bool("isInitialized"),
+  borsh.u64("counter"),
 ])
 
 async function addIntro(
@@ -34,6 +40,10 @@ async function addIntro(
   let [mint_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("token_mint")], programId)
   let [mint_auth_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("token_auth")], programId)
   let userTokenAcc = await token.getAssociatedTokenAddress(mint_pda, user.publicKey)
+  let [config_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("config")], programId)
+  let [tracker_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("rewards"), user.publicKey.toBuffer()], programId)
+  let [profile_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("profile"), user.publicKey.toBuffer()], programId)
+  let [marker_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("intro"), user.publicKey.toBuffer()], programId)
   const instruction = new web3.TransactionInstruction({
     keys: [
       {
@@ -75,6 +85,26 @@ async function addIntro(
         pubkey: token.TOKEN_PROGRAM_ID,
         isSigner: false,
         isWritable: false
+      },
+      {
+        pubkey: config_pda,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: tracker_pda,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: profile_pda,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: marker_pda,
+        isSigner: false,
+        isWritable: true,
       }
     ],
     programId,
@@ -97,6 +127,7 @@ async function updateIntro(
   buffer = buffer.slice(0, introInstructionLayout.getSpan(buffer))
   let [intro_pda] = await web3.PublicKey.findProgramAddress([user.publicKey.toBuffer(), Buffer.from(name)], programId)
   console.log("Intro PDA is", intro_pda.toBase58())
+  let [config_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("config")], programId)
   const instruction = new web3.TransactionInstruction({
     keys: [
       {
@@ -108,6 +139,16 @@ async function updateIntro(
         pubkey: intro_pda,
         isSigner: false,
         isWritable: true,
+      },
+      {
+        pubkey: web3.SystemProgram.programId,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: config_pda,
+        isSigner: false,
+        isWritable: false,
       }
     ],
     programId,
@@ -124,9 +165,6 @@ async function addReply(
   name: string,
   reply: string
 ) {
-  let buffer = Buffer.alloc(1000)
-  replyInstructionLayout.encode({ variant: 2, reply}, buffer)
-  buffer = buffer.slice(0, replyInstructionLayout.getSpan(buffer))
   let [intro_pda] = await web3.PublicKey.findProgramAddress([user.publicKey.toBuffer(), Buffer.from(name)], programId)
   console.log("Intro PDA:", intro_pda.toBase58())
   let [counter_pda] = await web3.PublicKey.findProgramAddress([intro_pda.toBuffer(), Buffer.from("reply")], programId)
@@ -135,15 +173,22 @@ async function addReply(
   let userTokenAcc = await token.getAssociatedTokenAddress(mint_pda, user.publicKey)
   console.log("Counter PDA:", counter_pda.toBase58())
   const account = await connection.getAccountInfo(counter_pda)
-  console.log("counter account", account);
-  const counter = StudentIntroCounter.deserialize(account?.data)
-  console.log(counter)
-  if (!counter) {
+  if (!account) {
     console.log("no counter account found")
     return;
   }
+  const counter = counterAccountLayout.decode(account.data)
+  console.log("Replies so far:", counter.counter.toString())
   let [reply_pda] = await web3.PublicKey.findProgramAddress([intro_pda.toBuffer(), new BN(counter.counter).toArrayLike(Buffer, "be", 8)], programId)
   console.log("Reply PDA:", reply_pda.toBase58())
+  let [, counterBump] = await web3.PublicKey.findProgramAddress([reply_pda.toBuffer(), Buffer.from("reply")], programId)
+  let buffer = Buffer.alloc(1000)
+  replyInstructionLayout.encode({ variant: 23, reply, counterBump }, buffer)
+  buffer = buffer.slice(0, replyInstructionLayout.getSpan(buffer))
+  let [config_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("config")], programId)
+  let [record_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("replier"), intro_pda.toBuffer(), user.publicKey.toBuffer()], programId)
+  let [tracker_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("rewards"), user.publicKey.toBuffer()], programId)
+  let [profile_pda] = await web3.PublicKey.findProgramAddress([Buffer.from("profile"), user.publicKey.toBuffer()], programId)
   const instruction = new web3.TransactionInstruction({
     keys: [
       {
@@ -190,6 +235,26 @@ async function addReply(
         pubkey: token.TOKEN_PROGRAM_ID,
         isSigner: false,
         isWritable: false
+      },
+      {
+        pubkey: config_pda,
+        isSigner: false,
+        isWritable: false,
+      },
+      {
+        pubkey: record_pda,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: tracker_pda,
+        isSigner: false,
+        isWritable: true,
+      },
+      {
+        pubkey: profile_pda,
+        isSigner: false,
+        isWritable: true,
       }
     ],
     programId,
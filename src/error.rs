@@ -1,7 +1,7 @@
 use solana_program::program_error::ProgramError;
 use thiserror::Error;
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum StudentIntroError {
     #[error("Pda account passed in not initialized")]
     UninitializedAccount,
@@ -13,6 +13,42 @@ pub enum StudentIntroError {
     InvalidStudentName,
     #[error("At least one of the account passed is incorrect")]
     IncorrectAccountPassed,
+    #[error("Instruction payload could not be decoded")]
+    MalformedPayload,
+    #[error("Account data could not be decoded")]
+    CorruptAccountData,
+    #[error("Signer is not authorized to perform this action")]
+    Unauthorized,
+    #[error("Account discriminator does not match the expected account type")]
+    InvalidDiscriminator,
+    #[error("Account layout version is not supported")]
+    UnsupportedAccountVersion,
+    #[error("User already reacted to this intro or reply")]
+    AlreadyReacted,
+    #[error("Wallet already posted an intro")]
+    IntroLimitReached,
+    #[error("Text is empty or only whitespace")]
+    EmptyText,
+    #[error("Text has leading or trailing whitespace")]
+    UntrimmedText,
+    #[error("Text is shorter than the configured minimum length")]
+    TextTooShort,
+    #[error("Text is longer than the configured maximum length")]
+    TextTooLong,
+    #[error("Text is not NFC normalized")]
+    UnnormalizedText,
+    #[error("Text contains a banned character")]
+    BannedCharacter,
+    #[error("Intro or reply is hidden by a moderator")]
+    ContentHidden,
+    #[error("User already reported this intro or reply")]
+    AlreadyReported,
+    #[error("Cohort is closed to new intros and replies")]
+    CohortClosed,
+    #[error("Name is longer than the 32 bytes of a PDA seed")]
+    SeedTooLong,
+    #[error("Name length limit exceeds the 32 bytes of a PDA seed")]
+    InvalidNameLimits,
 }
 
 impl From<StudentIntroError> for ProgramError {
@@ -0,0 +1,156 @@
+use crate::state::{ReactionKind, ReportReason};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+
+/// Prefix of the log lines written by `sol_log_data`.
+const LOG_DATA_PREFIX: &str = "Program data: ";
+
+/// Events logged by the program on every state change, Borsh encoded and
+/// written with `sol_log_data` so indexers can decode them from transaction logs.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum StudentIntroEvent {
+    IntroCreated {
+        intro: Pubkey,
+        identity: Pubkey,
+        name: String,
+        msg: String,
+    },
+    IntroUpdated {
+        intro: Pubkey,
+        msg: String,
+    },
+    IntroClosed {
+        intro: Pubkey,
+    },
+    ReplyAdded {
+        intro: Pubkey,
+        reply: Pubkey,
+        replier: Pubkey,
+        count: u64,
+    },
+    ReplyUpdated {
+        reply: Pubkey,
+    },
+    ReplyDeleted {
+        reply: Pubkey,
+    },
+    TokensMinted {
+        recipient: Pubkey,
+        amount: u64,
+    },
+    ConfigUpdated {
+        admin: Pubkey,
+    },
+    ReplyToReplyAdded {
+        intro: Pubkey,
+        parent_reply: Pubkey,
+        reply: Pubkey,
+        replier: Pubkey,
+        count: u64,
+    },
+    ReactionAdded {
+        target: Pubkey,
+        user: Pubkey,
+        kind: ReactionKind,
+    },
+    ReactionRemoved {
+        target: Pubkey,
+        user: Pubkey,
+        kind: ReactionKind,
+    },
+    IntroRenamed {
+        intro: Pubkey,
+        previous: Pubkey,
+        origin: Pubkey,
+        name: String,
+    },
+    ContentHidden {
+        target: Pubkey,
+        moderator: Pubkey,
+    },
+    ContentUnhidden {
+        target: Pubkey,
+        moderator: Pubkey,
+    },
+    ContentForceClosed {
+        target: Pubkey,
+        moderator: Pubkey,
+        author: Pubkey,
+    },
+    ContentReported {
+        target: Pubkey,
+        reporter: Pubkey,
+        reason: ReportReason,
+        report_count: u32,
+    },
+    ContentAutoHidden {
+        target: Pubkey,
+        report_count: u32,
+    },
+    CohortCreated {
+        cohort: Pubkey,
+        admin: Pubkey,
+        name: String,
+    },
+    CohortUpdated {
+        cohort: Pubkey,
+        admin: Pubkey,
+    },
+    MintInitialized {
+        mint: Pubkey,
+        decimals: u8,
+    },
+    MintMetadataCreated {
+        metadata: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    MintMetadataUpdated {
+        metadata: Pubkey,
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    AccountMigrated {
+        account: Pubkey,
+        len: u64,
+    },
+}
+
+impl StudentIntroEvent {
+    pub fn emit(&self) {
+        sol_log_data(&[&self.try_to_vec().unwrap()]);
+    }
+
+    /// Decodes an event from a `Program data: ` log line of this program,
+    /// returns `None` for any other line.
+    pub fn from_log(log: &str) -> Option<Self> {
+        let encoded = log.strip_prefix(LOG_DATA_PREFIX)?;
+        let data = base64::decode(encoded).ok()?;
+        Self::try_from_slice(&data).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_log() {
+        let event = StudentIntroEvent::ReplyAdded {
+            intro: Pubkey::new_unique(),
+            reply: Pubkey::new_unique(),
+            replier: Pubkey::new_unique(),
+            count: 3,
+        };
+        let log = format!(
+            "{}{}",
+            LOG_DATA_PREFIX,
+            base64::encode(event.try_to_vec().unwrap())
+        );
+        assert_eq!(StudentIntroEvent::from_log(&log), Some(event));
+        assert_eq!(StudentIntroEvent::from_log("Program log: minting"), None);
+        assert_eq!(StudentIntroEvent::from_log("Program data: !!"), None);
+    }
+}
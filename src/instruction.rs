@@ -1,52 +1,1364 @@
-use borsh::BorshDeserialize;
-use solana_program::program_error::ProgramError;
+use crate::error::StudentIntroError;
+use crate::state::{ReactionKind, ReportReason, MAX_MODERATORS};
+use crate::validation::TextLimits;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    bpf_loader_upgradeable,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGRAM_ID,
+    sysvar::rent::ID as SYSVAR_RENT_ID,
+};
+use spl_associated_token_account::get_associated_token_address;
+use spl_token::ID as TOKEN_PROGRAM_ID;
 
-#[derive(BorshDeserialize)]
-struct StudentIntroInstructionPayload {
-    name: String,
-    msg: String,
+/// Tunable program parameters stored in the config account.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigParams {
+    /// Tokens minted per intro, in base units of the mint.
+    pub intro_reward: u64,
+    /// Tokens minted per reply, in base units of the mint.
+    pub reply_reward: u64,
+    pub max_intro_size: u32,
+    /// Only takes effect if set before `InitializeMint`.
+    pub mint_decimals: u8,
+    /// Whether replies to one's own intro or reply are rewarded.
+    pub reward_self_replies: bool,
+    /// Replies of one replier rewarded under the same intro, threads included.
+    pub max_rewarded_replies_per_intro: u32,
+    /// Tokens a wallet can be rewarded per day, in base units of the mint.
+    /// `u64::MAX` disables the cap.
+    pub daily_reward_cap: u64,
+    /// Limits every wallet to a single intro instead of one per name. Closing
+    /// the intro does not allow posting another one.
+    pub one_intro_per_wallet: bool,
+    /// Length limits of intro and cohort names, which are also bounded by the
+    /// 32 bytes of a PDA seed. A `max_len` above 32 is rejected.
+    pub name_limits: TextLimits,
+    pub msg_limits: TextLimits,
+    pub reply_limits: TextLimits,
+    /// Wallets allowed to moderate content besides the admin, unused slots
+    /// hold the default pubkey.
+    pub moderators: [Pubkey; MAX_MODERATORS],
+    /// Reports that hide an intro or reply, 0 disables hiding on reports.
+    pub report_threshold: u32,
 }
-#[derive(BorshDeserialize)]
-pub struct ReplyPayload {
-    reply: String,
+
+impl Default for ConfigParams {
+    fn default() -> Self {
+        Self {
+            intro_reward: 10 * LAMPORTS_PER_SOL, //our token has 9 decimals, same as SOL
+            reply_reward: 5 * LAMPORTS_PER_SOL,
+            max_intro_size: 1000,
+            mint_decimals: 9,
+            reward_self_replies: false,
+            max_rewarded_replies_per_intro: 3,
+            daily_reward_cap: 50 * LAMPORTS_PER_SOL,
+            one_intro_per_wallet: false,
+            name_limits: TextLimits::new(1, 32),
+            msg_limits: TextLimits::new(1, 1000),
+            reply_limits: TextLimits::new(1, 500),
+            moderators: [Pubkey::default(); MAX_MODERATORS],
+            report_threshold: 5,
+        }
+    }
+}
+
+/// Settings of a cohort, managed by its admin.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct CohortParams {
+    /// Whether intros and replies may be posted in the cohort.
+    pub open: bool,
+    /// Overrides the config's intro reward, in base units of the mint.
+    pub intro_reward: Option<u64>,
+    /// Overrides the config's reply reward, in base units of the mint.
+    pub reply_reward: Option<u64>,
 }
 
+/// Compute units clients request for an `AddStudentIntro` instruction, covering
+/// an intro of `max_intro_size`. Regression tests fail if it needs more.
+pub const ADD_STUDENT_INTRO_COMPUTE_UNITS: u32 = 100_000;
+/// Compute units clients request for an `AddReply` instruction. Regression
+/// tests fail if it needs more.
+pub const ADD_REPLY_COMPUTE_UNITS: u32 = 80_000;
+
+/// Instructions understood by the program. The Borsh encoding of this enum is
+/// the wire format: a one byte variant index followed by the variant's fields,
+/// so variants must only ever be appended.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 pub enum StudentIntroInstruction {
-    AddStudentIntro { name: String, msg: String },
-    UpdateStudentIntro { name: String, msg: String },
-    AddReply { reply: String },
+    /// Creates an intro and rewards its author, up to the daily reward cap. The
+    /// author's profile is created with the intro's name on its first post.
+    /// Fails if the wallet already posted an intro while the config limits
+    /// wallets to one.
+    AddStudentIntro {
+        name: String,
+        msg: String,
+    },
+    UpdateStudentIntro {
+        name: String,
+        msg: String,
+    },
+    /// Replies to an intro. Self replies, replies past the per intro limit and
+    /// replies past the replier's daily reward cap are not rewarded. Counts
+    /// towards the replier's profile. Replies in a cohort's thread also take the
+    /// cohort, which must be open.
+    AddReply {
+        reply: String,
+    },
     InitializeMint,
+    /// Closes an intro and its reply counter, signed by the intro's identity.
+    /// Replies already posted under the intro are left untouched, their rent
+    /// belongs to the repliers. The counter of an intro with replies stays to
+    /// keep their seeds taken, and an intro posted at the same address takes
+    /// it over.
+    CloseStudentIntro,
+    /// Replaces the text of a reply, signed by its replier. The account is
+    /// resized to fit and rent is settled with the replier.
+    UpdateReply {
+        reply: String,
+    },
+    /// Closes a reply and its own reply counter if it has one, signed by its
+    /// replier who receives the rent. The intro's reply counter is not
+    /// decremented since reply PDAs are seeded by it, and replies posted under
+    /// the closed reply are left untouched.
+    DeleteReply,
+    /// Creates the config account, signed by the program's upgrade authority
+    /// which becomes the config admin.
+    InitializeConfig {
+        params: ConfigParams,
+    },
+    /// Updates the config account, signed by the current admin who pays for
+    /// upgrading an older config layout.
+    UpdateConfig {
+        admin: Pubkey,
+        params: ConfigParams,
+    },
+    /// Creates the Metaplex metadata account of the reward mint, signed by the
+    /// config admin. The `token_auth` PDA is its update authority.
+    CreateMintMetadata {
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    /// Updates the metadata of the reward mint, signed by the config admin.
+    UpdateMintMetadata {
+        name: String,
+        symbol: String,
+        uri: String,
+    },
+    /// Rewrites one program account of any older layout version into the
+    /// current one. Permissionless, a signing payer tops up rent when the
+    /// account grows and a shrinking account keeps its lamports until it is
+    /// closed.
+    MigrateAccount,
+    /// Posts a reply under another reply, with the same accounts as `AddReply`
+    /// where the parent reply replaces the intro, followed by the thread's
+    /// intro which must not be hidden. The parent's reply counter is created,
+    /// paid by the replier, with its first reply.
+    AddReplyToReply {
+        reply: String,
+    },
+    /// Reacts to an intro or reply, creating the signer's reaction PDA for the
+    /// target and counting it on the target. A user reacts once per target.
+    /// Reactions to an intro are keyed by its thread, its original address, so
+    /// they follow it across renames. A reaction left on an intro closed since
+    /// is taken over for the intro now posted at its address.
+    React {
+        kind: ReactionKind,
+    },
+    /// Removes the signer's reaction, refunding its rent to the signer. A
+    /// reaction left on an intro closed since is not uncounted from the intro
+    /// now posted at its address.
+    Unreact,
+    /// Moves the signer's intro `name` to the PDA of `new_name` and closes the
+    /// old one, refunding its rent to the signer. The reply counter, replies,
+    /// reactions and reaction counts carry over, replies stay seeded under the
+    /// intro's original address which the renamed intro records as its origin.
+    /// The signer's intro marker is moved along when it marks the intro.
+    RenameStudentIntro {
+        name: String,
+        new_name: String,
+    },
+    /// Flags an intro or reply as hidden, signed by the admin or a moderator
+    /// who pays for upgrading an older layout. Hidden content takes no replies,
+    /// neither do the replies in the thread of a hidden intro.
+    HideContent,
+    /// Clears the hidden flag and the report count of an intro or reply, signed
+    /// by the admin or a moderator.
+    UnhideContent,
+    /// Closes an intro or a reply with its reply counter, signed by the admin
+    /// or a moderator. The rent is refunded to the author. The counter of an
+    /// intro with replies stays, as with `CloseStudentIntro`.
+    ForceClose,
+    /// Reports an intro or reply, creating the signer's report PDA for the
+    /// target and counting it on the target. A user reports once per target,
+    /// the target is hidden once its reports reach the config's threshold.
+    /// Reports on an intro are keyed by its thread, so renaming it does not
+    /// take new reports from earlier reporters.
+    ReportContent {
+        reason: ReportReason,
+    },
+    /// Creates the cohort `name` managed by `admin`, signed by the config admin
+    /// who pays for it.
+    CreateCohort {
+        name: String,
+        admin: Pubkey,
+        params: CohortParams,
+    },
+    /// Updates a cohort, signed by its current admin who tops up the rent of a
+    /// grown account. A shrinking account keeps its excess lamports.
+    UpdateCohort {
+        admin: Pubkey,
+        params: CohortParams,
+    },
+    /// Creates an intro in a cohort, with the accounts of `AddStudentIntro`
+    /// followed by the cohort which must be open. The intro and the wallet's
+    /// intro marker are seeded under the cohort, whose reward overrides apply.
+    AddCohortIntro {
+        name: String,
+        msg: String,
+    },
+    /// `AddReply` taking the bump of the new reply's own reply counter PDA,
+    /// which is stored for replies posted under it, so the program does not
+    /// search for it. The bump must be the canonical one.
+    AddReplyWithCounterBump {
+        reply: String,
+        counter_bump: u8,
+    },
+    /// `AddReplyToReply` taking the new reply's counter bump, as
+    /// `AddReplyWithCounterBump` does.
+    AddReplyToReplyWithCounterBump {
+        reply: String,
+        counter_bump: u8,
+    },
 }
 
 impl StudentIntroInstruction {
     pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
-        let (&variant, rest) = data
-            .split_first()
-            .ok_or(ProgramError::InvalidInstructionData)?;
-
-        Ok(match variant {
-            0 => {
-                let payload = StudentIntroInstructionPayload::try_from_slice(rest).unwrap();
-                Self::AddStudentIntro {
-                    name: payload.name,
-                    msg: payload.msg,
-                }
-            }
-            1 => {
-                let payload = StudentIntroInstructionPayload::try_from_slice(rest).unwrap();
-                Self::UpdateStudentIntro {
-                    name: payload.name,
-                    msg: payload.msg,
-                }
+        let &variant = data.first().ok_or(ProgramError::InvalidInstructionData)?;
+        Self::try_from_slice(data).map_err(|_| {
+            if Self::is_variant(variant) {
+                StudentIntroError::MalformedPayload.into()
+            } else {
+                ProgramError::InvalidInstructionData
             }
-            2 => {
-                let payload = ReplyPayload::try_from_slice(rest).unwrap();
-                Self::AddReply {
-                    reply: payload.reply,
-                }
-            }
-            3 => Self::InitializeMint,
-            _ => return Err(ProgramError::InvalidInstructionData),
         })
     }
+
+    /// Whether `variant` is the index of a variant of this enum. Every variant
+    /// decodes from zeroed fields, whose encoding is never longer than the enum
+    /// in memory.
+    fn is_variant(variant: u8) -> bool {
+        let mut zeroed = vec![0; 1 + std::mem::size_of::<Self>()];
+        zeroed[0] = variant;
+        Self::deserialize(&mut &zeroed[..]).is_ok()
+    }
+
+    pub fn pack(&self) -> Vec<u8> {
+        self.try_to_vec().unwrap()
+    }
+}
+
+/// Derives the reward token mint PDA.
+pub fn find_mint_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_mint"], program_id)
+}
+
+/// Derives the PDA that acts as mint authority of the reward token.
+pub fn find_mint_auth_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"token_auth"], program_id)
+}
+
+/// Derives the program config PDA.
+pub fn find_config_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"config"], program_id)
+}
+
+/// Derives the Metaplex metadata account of the reward mint.
+pub fn find_mint_metadata_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    let (mint, _) = find_mint_address(program_id);
+    mpl_token_metadata::pda::find_metadata_account(&mint)
+}
+
+/// Derives the program data account of the upgradeable loader, it records
+/// the program's upgrade authority.
+pub fn find_program_data_address(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id())
+}
+
+/// Derives the intro PDA of `initializer` for the given student `name`.
+pub fn find_intro_address(program_id: &Pubkey, initializer: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[initializer.as_ref(), name.as_bytes()], program_id)
+}
+
+/// Derives the intro PDA of `initializer` for the given student `name` in
+/// `cohort`.
+pub fn find_cohort_intro_address(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    initializer: &Pubkey,
+    name: &str,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&intro_seeds(Some(cohort), initializer, name), program_id)
+}
+
+/// Seeds of an intro PDA without its bump, intros posted in a cohort are
+/// seeded under it.
+pub fn intro_seeds<'a>(
+    cohort: Option<&'a Pubkey>,
+    initializer: &'a Pubkey,
+    name: &'a str,
+) -> Vec<&'a [u8]> {
+    let mut seeds = Vec::with_capacity(3);
+    if let Some(cohort) = cohort {
+        seeds.push(cohort.as_ref());
+    }
+    seeds.push(initializer.as_ref());
+    seeds.push(name.as_bytes());
+    seeds
+}
+
+/// Derives the reply counter PDA belonging to an intro or a reply.
+pub fn find_counter_address(program_id: &Pubkey, intro: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[intro.as_ref(), b"reply"], program_id)
+}
+
+/// Derives the PDA of the reply number `count` posted under an intro or a reply.
+pub fn find_reply_address(program_id: &Pubkey, intro: &Pubkey, count: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[intro.as_ref(), count.to_be_bytes().as_ref()], program_id)
+}
+
+/// Derives the PDA counting the rewarded replies of `replier` under an intro.
+pub fn find_replier_record_address(
+    program_id: &Pubkey,
+    intro: &Pubkey,
+    replier: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"replier", intro.as_ref(), replier.as_ref()], program_id)
+}
+
+/// Derives the PDA tracking the rewards minted to `user` today.
+pub fn find_reward_tracker_address(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"rewards", user.as_ref()], program_id)
+}
+
+/// Derives the profile PDA of `user`.
+pub fn find_user_profile_address(program_id: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"profile", user.as_ref()], program_id)
+}
+
+/// Derives the PDA marking that `wallet` posted an intro.
+pub fn find_intro_marker_address(program_id: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&intro_marker_seeds(None, wallet), program_id)
+}
+
+/// Derives the PDA marking that `wallet` posted an intro in `cohort`.
+pub fn find_cohort_intro_marker_address(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    wallet: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&intro_marker_seeds(Some(cohort), wallet), program_id)
+}
+
+/// Seeds of an intro marker PDA without its bump, wallets are marked per
+/// cohort.
+pub fn intro_marker_seeds<'a>(cohort: Option<&'a Pubkey>, wallet: &'a Pubkey) -> Vec<&'a [u8]> {
+    let mut seeds: Vec<&[u8]> = Vec::with_capacity(3);
+    seeds.push(b"intro");
+    if let Some(cohort) = cohort {
+        seeds.push(cohort.as_ref());
+    }
+    seeds.push(wallet.as_ref());
+    seeds
+}
+
+/// Derives the PDA of the cohort `name`.
+pub fn find_cohort_address(program_id: &Pubkey, name: &str) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"cohort", name.as_bytes()], program_id)
+}
+
+/// Derives the PDA recording the reaction of `user` to an intro or reply.
+pub fn find_reaction_address(program_id: &Pubkey, target: &Pubkey, user: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"reaction", target.as_ref(), user.as_ref()], program_id)
+}
+
+/// Derives the PDA recording the report of `reporter` on an intro or reply.
+pub fn find_report_address(
+    program_id: &Pubkey,
+    target: &Pubkey,
+    reporter: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"report", target.as_ref(), reporter.as_ref()], program_id)
+}
+
+/// Creates an `AddStudentIntro` instruction, `payer` becomes the intro's identity
+/// and receives the reward tokens in its associated token account.
+pub fn add_student_intro(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    name: String,
+    msg: String,
+) -> Instruction {
+    let (intro, _) = find_intro_address(program_id, payer, &name);
+    let (marker, _) = find_intro_marker_address(program_id, payer);
+    let data = StudentIntroInstruction::AddStudentIntro { name, msg }.pack();
+    intro_instruction(program_id, payer, &intro, &marker, data)
+}
+
+/// Creates an `AddCohortIntro` instruction posting the intro of `payer` in
+/// `cohort`.
+pub fn add_cohort_intro(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    cohort: &Pubkey,
+    name: String,
+    msg: String,
+) -> Instruction {
+    let (intro, _) = find_cohort_intro_address(program_id, cohort, payer, &name);
+    let (marker, _) = find_cohort_intro_marker_address(program_id, cohort, payer);
+    let data = StudentIntroInstruction::AddCohortIntro { name, msg }.pack();
+    let mut ix = intro_instruction(program_id, payer, &intro, &marker, data);
+    ix.accounts.push(AccountMeta::new_readonly(*cohort, false));
+    ix
+}
+
+fn intro_instruction(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    intro: &Pubkey,
+    marker: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (mint, _) = find_mint_address(program_id);
+    let (mint_auth, _) = find_mint_auth_address(program_id);
+    let (counter, _) = find_counter_address(program_id, intro);
+    let (reward_tracker, _) = find_reward_tracker_address(program_id, payer);
+    let (profile, _) = find_user_profile_address(program_id, payer);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*intro, false),
+            AccountMeta::new(counter, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(mint_auth, false),
+            AccountMeta::new(get_associated_token_address(payer, &mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(reward_tracker, false),
+            AccountMeta::new(profile, false),
+            AccountMeta::new(*marker, false),
+        ],
+        data,
+    }
+}
+
+/// Creates an `UpdateStudentIntro` instruction replacing the message of the
+/// intro `initializer` created under `name`.
+pub fn update_student_intro(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    name: String,
+    msg: String,
+) -> Instruction {
+    update_intro_instruction(program_id, None, initializer, name, msg)
+}
+
+/// Creates an `UpdateStudentIntro` instruction replacing the message of the
+/// intro `initializer` created under `name` in `cohort`.
+pub fn update_cohort_intro(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    initializer: &Pubkey,
+    name: String,
+    msg: String,
+) -> Instruction {
+    update_intro_instruction(program_id, Some(cohort), initializer, name, msg)
+}
+
+fn update_intro_instruction(
+    program_id: &Pubkey,
+    cohort: Option<&Pubkey>,
+    initializer: &Pubkey,
+    name: String,
+    msg: String,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (intro, _) =
+        Pubkey::find_program_address(&intro_seeds(cohort, initializer, &name), program_id);
+    let data = StudentIntroInstruction::UpdateStudentIntro { name, msg }.pack();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(intro, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data,
+    }
+}
+
+/// Creates an `AddReply` instruction for `intro`. `reply_count` must be the
+/// current value stored in the intro's reply counter account.
+pub fn add_reply(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    intro: &Pubkey,
+    reply_count: u64,
+    reply: String,
+) -> Instruction {
+    reply_instruction(
+        program_id,
+        replier,
+        intro,
+        intro,
+        intro,
+        reply_count,
+        |counter_bump| StudentIntroInstruction::AddReplyWithCounterBump {
+            reply,
+            counter_bump,
+        },
+    )
+}
+
+/// Creates an `AddReply` instruction for `intro` after it was renamed, replies
+/// are seeded under its `origin`.
+pub fn add_reply_to_renamed_intro(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    intro: &Pubkey,
+    origin: &Pubkey,
+    reply_count: u64,
+    reply: String,
+) -> Instruction {
+    reply_instruction(
+        program_id,
+        replier,
+        origin,
+        intro,
+        origin,
+        reply_count,
+        |counter_bump| StudentIntroInstruction::AddReplyWithCounterBump {
+            reply,
+            counter_bump,
+        },
+    )
+}
+
+/// Creates an `AddReplyToReply` instruction for `parent_reply`, posted in the
+/// thread of `intro`. `reply_count` must be the current value stored in the
+/// parent's reply counter account, 0 if it does not exist yet.
+pub fn add_reply_to_reply(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    intro: &Pubkey,
+    parent_reply: &Pubkey,
+    reply_count: u64,
+    reply: String,
+) -> Instruction {
+    add_reply_to_reply_in_renamed_intro(
+        program_id,
+        replier,
+        intro,
+        intro,
+        parent_reply,
+        reply_count,
+        reply,
+    )
+}
+
+/// Creates an `AddReplyToReply` instruction for `parent_reply`, posted in the
+/// thread of `intro` after it was renamed from its `origin`.
+pub fn add_reply_to_reply_in_renamed_intro(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    intro: &Pubkey,
+    origin: &Pubkey,
+    parent_reply: &Pubkey,
+    reply_count: u64,
+    reply: String,
+) -> Instruction {
+    let mut ix = reply_instruction(
+        program_id,
+        replier,
+        origin,
+        parent_reply,
+        parent_reply,
+        reply_count,
+        |counter_bump| StudentIntroInstruction::AddReplyToReplyWithCounterBump {
+            reply,
+            counter_bump,
+        },
+    );
+    ix.accounts.push(AccountMeta::new_readonly(*intro, false));
+    ix
+}
+
+/// Appends `cohort` to an `AddReply` or `AddReplyToReply` instruction, which
+/// replies in the thread of an intro posted in that cohort require.
+pub fn with_cohort(mut ix: Instruction, cohort: &Pubkey) -> Instruction {
+    ix.accounts.push(AccountMeta::new_readonly(*cohort, false));
+    ix
+}
+
+/// `intro` is the thread's original intro address, `parent_account` the intro
+/// or reply replied to and `parent` the address its replies are seeded under.
+/// `make_data` builds the instruction from the new reply's counter bump.
+fn reply_instruction(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    intro: &Pubkey,
+    parent_account: &Pubkey,
+    parent: &Pubkey,
+    reply_count: u64,
+    make_data: impl FnOnce(u8) -> StudentIntroInstruction,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (mint, _) = find_mint_address(program_id);
+    let (mint_auth, _) = find_mint_auth_address(program_id);
+    let (counter, _) = find_counter_address(program_id, parent);
+    let (reply_pda, _) = find_reply_address(program_id, parent, reply_count);
+    let (_, reply_counter_bump) = find_counter_address(program_id, &reply_pda);
+    let (replier_record, _) = find_replier_record_address(program_id, intro, replier);
+    let (reward_tracker, _) = find_reward_tracker_address(program_id, replier);
+    let (profile, _) = find_user_profile_address(program_id, replier);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*replier, true),
+            AccountMeta::new_readonly(*parent_account, false),
+            AccountMeta::new(counter, false),
+            AccountMeta::new(reply_pda, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(mint_auth, false),
+            AccountMeta::new(get_associated_token_address(replier, &mint), false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(replier_record, false),
+            AccountMeta::new(reward_tracker, false),
+            AccountMeta::new(profile, false),
+        ],
+        data: make_data(reply_counter_bump).pack(),
+    }
+}
+
+/// Creates an `InitializeMint` instruction, `initializer` must be the config
+/// admin and pays for the mint account.
+pub fn initialize_mint(program_id: &Pubkey, initializer: &Pubkey) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (mint, _) = find_mint_address(program_id);
+    let (mint_auth, _) = find_mint_auth_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(mint_auth, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSVAR_RENT_ID, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: StudentIntroInstruction::InitializeMint.pack(),
+    }
+}
+
+/// Creates a `CloseStudentIntro` instruction, the rent of the intro and its
+/// reply counter is sent to `destination`.
+pub fn close_student_intro(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    name: &str,
+    destination: &Pubkey,
+) -> Instruction {
+    close_intro_instruction(program_id, None, initializer, name, None, destination)
+}
+
+/// Creates a `CloseStudentIntro` instruction for an intro renamed to `name`,
+/// whose reply counter is seeded under its `origin`.
+pub fn close_renamed_student_intro(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    name: &str,
+    origin: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    close_intro_instruction(
+        program_id,
+        None,
+        initializer,
+        name,
+        Some(origin),
+        destination,
+    )
+}
+
+/// Creates a `CloseStudentIntro` instruction for the intro `initializer`
+/// created under `name` in `cohort`.
+pub fn close_cohort_intro(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    initializer: &Pubkey,
+    name: &str,
+    destination: &Pubkey,
+) -> Instruction {
+    close_intro_instruction(
+        program_id,
+        Some(cohort),
+        initializer,
+        name,
+        None,
+        destination,
+    )
+}
+
+/// Creates a `CloseStudentIntro` instruction for an intro in `cohort` renamed
+/// to `name`, whose reply counter is seeded under its `origin`.
+pub fn close_renamed_cohort_intro(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    initializer: &Pubkey,
+    name: &str,
+    origin: &Pubkey,
+    destination: &Pubkey,
+) -> Instruction {
+    close_intro_instruction(
+        program_id,
+        Some(cohort),
+        initializer,
+        name,
+        Some(origin),
+        destination,
+    )
+}
+
+/// `origin` is the address a renamed intro's reply counter is seeded under.
+fn close_intro_instruction(
+    program_id: &Pubkey,
+    cohort: Option<&Pubkey>,
+    initializer: &Pubkey,
+    name: &str,
+    origin: Option<&Pubkey>,
+    destination: &Pubkey,
+) -> Instruction {
+    let (intro, _) =
+        Pubkey::find_program_address(&intro_seeds(cohort, initializer, name), program_id);
+    let (counter, _) = find_counter_address(program_id, origin.unwrap_or(&intro));
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*initializer, true),
+            AccountMeta::new(intro, false),
+            AccountMeta::new(counter, false),
+            AccountMeta::new(*destination, false),
+        ],
+        data: StudentIntroInstruction::CloseStudentIntro.pack(),
+    }
+}
+
+/// Creates an `UpdateReply` instruction replacing the text of `reply_account`.
+pub fn update_reply(
+    program_id: &Pubkey,
+    replier: &Pubkey,
+    reply_account: &Pubkey,
+    reply: String,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*replier, true),
+            AccountMeta::new(*reply_account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+        ],
+        data: StudentIntroInstruction::UpdateReply { reply }.pack(),
+    }
+}
+
+/// Creates a `DeleteReply` instruction closing `reply_account` and its reply
+/// counter.
+pub fn delete_reply(program_id: &Pubkey, replier: &Pubkey, reply_account: &Pubkey) -> Instruction {
+    let (counter, _) = find_counter_address(program_id, reply_account);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*replier, true),
+            AccountMeta::new(*reply_account, false),
+            AccountMeta::new(counter, false),
+        ],
+        data: StudentIntroInstruction::DeleteReply.pack(),
+    }
+}
+
+/// Creates an `InitializeConfig` instruction making `admin`, the program's
+/// upgrade authority, the config admin.
+pub fn initialize_config(program_id: &Pubkey, admin: &Pubkey, params: ConfigParams) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (program_data, _) = find_program_data_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(program_data, false),
+        ],
+        data: StudentIntroInstruction::InitializeConfig { params }.pack(),
+    }
+}
+
+/// Creates an `UpdateConfig` instruction, `new_admin` may equal `admin`.
+pub fn update_config(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    new_admin: &Pubkey,
+    params: ConfigParams,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: StudentIntroInstruction::UpdateConfig {
+            admin: *new_admin,
+            params,
+        }
+        .pack(),
+    }
+}
+
+/// Creates a `CreateMintMetadata` instruction, `admin` pays for the metadata account.
+pub fn create_mint_metadata(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (mint, _) = find_mint_address(program_id);
+    let (mint_auth, _) = find_mint_auth_address(program_id);
+    let (metadata, _) = find_mint_metadata_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(mint_auth, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(SYSVAR_RENT_ID, false),
+            AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        ],
+        data: StudentIntroInstruction::CreateMintMetadata { name, symbol, uri }.pack(),
+    }
+}
+
+/// Creates an `UpdateMintMetadata` instruction.
+pub fn update_mint_metadata(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (mint_auth, _) = find_mint_auth_address(program_id);
+    let (metadata, _) = find_mint_metadata_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(mint_auth, false),
+            AccountMeta::new_readonly(mpl_token_metadata::id(), false),
+        ],
+        data: StudentIntroInstruction::UpdateMintMetadata { name, symbol, uri }.pack(),
+    }
+}
+
+/// Creates a `MigrateAccount` instruction for `account`, `payer` covers any
+/// rent increase.
+pub fn migrate_account(program_id: &Pubkey, payer: &Pubkey, account: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: StudentIntroInstruction::MigrateAccount.pack(),
+    }
+}
+
+/// Creates a `React` instruction of `user` to the intro or reply `target`.
+pub fn react(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    target: &Pubkey,
+    kind: ReactionKind,
+) -> Instruction {
+    let data = StudentIntroInstruction::React { kind }.pack();
+    reaction_instruction(program_id, user, target, target, data)
+}
+
+/// Creates a `React` instruction of `user` to `intro` after it was renamed,
+/// reactions are keyed by its `origin`.
+pub fn react_to_renamed_intro(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    intro: &Pubkey,
+    origin: &Pubkey,
+    kind: ReactionKind,
+) -> Instruction {
+    let data = StudentIntroInstruction::React { kind }.pack();
+    reaction_instruction(program_id, user, intro, origin, data)
+}
+
+/// Creates an `Unreact` instruction removing the reaction of `user` to `target`.
+pub fn unreact(program_id: &Pubkey, user: &Pubkey, target: &Pubkey) -> Instruction {
+    let data = StudentIntroInstruction::Unreact.pack();
+    reaction_instruction(program_id, user, target, target, data)
+}
+
+/// Creates an `Unreact` instruction removing the reaction of `user` to `intro`
+/// after it was renamed, reactions are keyed by its `origin`.
+pub fn unreact_from_renamed_intro(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    intro: &Pubkey,
+    origin: &Pubkey,
+) -> Instruction {
+    let data = StudentIntroInstruction::Unreact.pack();
+    reaction_instruction(program_id, user, intro, origin, data)
+}
+
+/// `target` is the intro or reply reacted to and `key` the address its
+/// reactions are seeded under.
+fn reaction_instruction(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    target: &Pubkey,
+    key: &Pubkey,
+    data: Vec<u8>,
+) -> Instruction {
+    let (reaction, _) = find_reaction_address(program_id, key, user);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(*target, false),
+            AccountMeta::new(reaction, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data,
+    }
+}
+
+/// Creates a `RenameStudentIntro` instruction moving the intro `initializer`
+/// created under `name` to `new_name`.
+pub fn rename_student_intro(
+    program_id: &Pubkey,
+    initializer: &Pubkey,
+    name: String,
+    new_name: String,
+) -> Instruction {
+    rename_intro_instruction(program_id, None, initializer, name, new_name)
+}
+
+/// Creates a `RenameStudentIntro` instruction moving the intro `initializer`
+/// created under `name` in `cohort` to `new_name`, renamed intros stay in
+/// their cohort.
+pub fn rename_cohort_intro(
+    program_id: &Pubkey,
+    cohort: &Pubkey,
+    initializer: &Pubkey,
+    name: String,
+    new_name: String,
+) -> Instruction {
+    rename_intro_instruction(program_id, Some(cohort), initializer, name, new_name)
+}
+
+fn rename_intro_instruction(
+    program_id: &Pubkey,
+    cohort: Option<&Pubkey>,
+    initializer: &Pubkey,
+    name: String,
+    new_name: String,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (intro, _) =
+        Pubkey::find_program_address(&intro_seeds(cohort, initializer, &name), program_id);
+    let (new_intro, _) =
+        Pubkey::find_program_address(&intro_seeds(cohort, initializer, &new_name), program_id);
+    let (marker, _) =
+        Pubkey::find_program_address(&intro_marker_seeds(cohort, initializer), program_id);
+    let data = StudentIntroInstruction::RenameStudentIntro { name, new_name }.pack();
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*initializer, true),
+            AccountMeta::new(intro, false),
+            AccountMeta::new(new_intro, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(marker, false),
+        ],
+        data,
+    }
+}
+
+/// Creates a `HideContent` instruction for the intro or reply `target`.
+pub fn hide_content(program_id: &Pubkey, moderator: &Pubkey, target: &Pubkey) -> Instruction {
+    moderation_instruction(
+        program_id,
+        moderator,
+        target,
+        StudentIntroInstruction::HideContent,
+    )
+}
+
+/// Creates an `UnhideContent` instruction for the intro or reply `target`.
+pub fn unhide_content(program_id: &Pubkey, moderator: &Pubkey, target: &Pubkey) -> Instruction {
+    moderation_instruction(
+        program_id,
+        moderator,
+        target,
+        StudentIntroInstruction::UnhideContent,
+    )
+}
+
+fn moderation_instruction(
+    program_id: &Pubkey,
+    moderator: &Pubkey,
+    target: &Pubkey,
+    ix: StudentIntroInstruction,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*moderator, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(*target, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: ix.pack(),
+    }
+}
+
+/// Creates a `ForceClose` instruction for the intro or reply `target` posted by
+/// `author`, with the target's reply `counter`. A renamed intro's counter is
+/// seeded under its origin, a reply's under the reply.
+pub fn force_close(
+    program_id: &Pubkey,
+    moderator: &Pubkey,
+    target: &Pubkey,
+    author: &Pubkey,
+    counter: &Pubkey,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*moderator, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(*target, false),
+            AccountMeta::new(*author, false),
+            AccountMeta::new(*counter, false),
+        ],
+        data: StudentIntroInstruction::ForceClose.pack(),
+    }
+}
+
+/// Creates a `ReportContent` instruction of `reporter` on the intro or reply
+/// `target`.
+pub fn report_content(
+    program_id: &Pubkey,
+    reporter: &Pubkey,
+    target: &Pubkey,
+    reason: ReportReason,
+) -> Instruction {
+    report_instruction(program_id, reporter, target, target, reason)
+}
+
+/// Creates a `ReportContent` instruction of `reporter` on `intro` after it was
+/// renamed, reports are keyed by its `origin`.
+pub fn report_renamed_intro(
+    program_id: &Pubkey,
+    reporter: &Pubkey,
+    intro: &Pubkey,
+    origin: &Pubkey,
+    reason: ReportReason,
+) -> Instruction {
+    report_instruction(program_id, reporter, intro, origin, reason)
+}
+
+/// `target` is the intro or reply reported and `key` the address its reports
+/// are seeded under.
+fn report_instruction(
+    program_id: &Pubkey,
+    reporter: &Pubkey,
+    target: &Pubkey,
+    key: &Pubkey,
+    reason: ReportReason,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (report, _) = find_report_address(program_id, key, reporter);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*reporter, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(*target, false),
+            AccountMeta::new(report, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: StudentIntroInstruction::ReportContent { reason }.pack(),
+    }
+}
+
+/// Creates a `CreateCohort` instruction for the cohort `name`, signed and paid
+/// for by the config admin.
+pub fn create_cohort(
+    program_id: &Pubkey,
+    config_admin: &Pubkey,
+    name: String,
+    admin: &Pubkey,
+    params: CohortParams,
+) -> Instruction {
+    let (config, _) = find_config_address(program_id);
+    let (cohort, _) = find_cohort_address(program_id, &name);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*config_admin, true),
+            AccountMeta::new_readonly(config, false),
+            AccountMeta::new(cohort, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: StudentIntroInstruction::CreateCohort {
+            name,
+            admin: *admin,
+            params,
+        }
+        .pack(),
+    }
+}
+
+/// Creates an `UpdateCohort` instruction for the cohort `name`, `new_admin` may
+/// equal `admin`.
+pub fn update_cohort(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    name: &str,
+    new_admin: &Pubkey,
+    params: CohortParams,
+) -> Instruction {
+    let (cohort, _) = find_cohort_address(program_id, name);
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(cohort, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+        ],
+        data: StudentIntroInstruction::UpdateCohort {
+            admin: *new_admin,
+            params,
+        }
+        .pack(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        let long = "x".repeat(900);
+        let instructions = vec![
+            StudentIntroInstruction::AddStudentIntro {
+                name: "Naruto".to_owned(),
+                msg: "Developing solana jutsu".to_owned(),
+            },
+            StudentIntroInstruction::AddStudentIntro {
+                name: String::new(),
+                msg: String::new(),
+            },
+            StudentIntroInstruction::UpdateStudentIntro {
+                name: "うずまき".to_owned(),
+                msg: long.clone(),
+            },
+            StudentIntroInstruction::AddReply { reply: long },
+            StudentIntroInstruction::AddReply {
+                reply: "All the best Naruto 🍜".to_owned(),
+            },
+            StudentIntroInstruction::InitializeMint,
+            StudentIntroInstruction::CloseStudentIntro,
+            StudentIntroInstruction::UpdateReply {
+                reply: "Typo fixed".to_owned(),
+            },
+            StudentIntroInstruction::DeleteReply,
+            StudentIntroInstruction::InitializeConfig {
+                params: ConfigParams::default(),
+            },
+            StudentIntroInstruction::UpdateConfig {
+                admin: Pubkey::new_unique(),
+                params: ConfigParams {
+                    intro_reward: 1,
+                    reply_reward: 0,
+                    max_intro_size: 500,
+                    mint_decimals: 0,
+                    reward_self_replies: true,
+                    max_rewarded_replies_per_intro: 1,
+                    daily_reward_cap: u64::MAX,
+                    one_intro_per_wallet: true,
+                    name_limits: TextLimits::new(2, 20),
+                    msg_limits: TextLimits::new(0, 100),
+                    reply_limits: TextLimits::new(1, 50),
+                    moderators: [Pubkey::new_unique(); MAX_MODERATORS],
+                    report_threshold: 0,
+                },
+            },
+            StudentIntroInstruction::CreateMintMetadata {
+                name: "Student Intro Token".to_owned(),
+                symbol: "INTRO".to_owned(),
+                uri: "https://example.com/intro.json".to_owned(),
+            },
+            StudentIntroInstruction::UpdateMintMetadata {
+                name: String::new(),
+                symbol: String::new(),
+                uri: String::new(),
+            },
+            StudentIntroInstruction::MigrateAccount,
+            StudentIntroInstruction::AddReplyToReply {
+                reply: "Thanks!".to_owned(),
+            },
+            StudentIntroInstruction::React {
+                kind: ReactionKind::Celebrate,
+            },
+            StudentIntroInstruction::Unreact,
+            StudentIntroInstruction::RenameStudentIntro {
+                name: "Naruto".to_owned(),
+                new_name: "Naruto Uzumaki".to_owned(),
+            },
+            StudentIntroInstruction::HideContent,
+            StudentIntroInstruction::UnhideContent,
+            StudentIntroInstruction::ForceClose,
+            StudentIntroInstruction::ReportContent {
+                reason: ReportReason::Harassment,
+            },
+            StudentIntroInstruction::CreateCohort {
+                name: "Class of 2026".to_owned(),
+                admin: Pubkey::new_unique(),
+                params: CohortParams {
+                    open: true,
+                    intro_reward: Some(20),
+                    reply_reward: None,
+                },
+            },
+            StudentIntroInstruction::UpdateCohort {
+                admin: Pubkey::new_unique(),
+                params: CohortParams {
+                    open: false,
+                    intro_reward: None,
+                    reply_reward: Some(0),
+                },
+            },
+            StudentIntroInstruction::AddCohortIntro {
+                name: "Naruto".to_owned(),
+                msg: "Developing solana jutsu".to_owned(),
+            },
+            StudentIntroInstruction::AddReplyWithCounterBump {
+                reply: "All the best Naruto 🍜".to_owned(),
+                counter_bump: 255,
+            },
+            StudentIntroInstruction::AddReplyToReplyWithCounterBump {
+                reply: "Thanks!".to_owned(),
+                counter_bump: 0,
+            },
+        ];
+        let mut indices = Vec::new();
+        for ix in instructions {
+            let data = ix.pack();
+            assert!(StudentIntroInstruction::is_variant(data[0]));
+            indices.push(data[0]);
+            assert_eq!(StudentIntroInstruction::unpack(&data).unwrap(), ix);
+        }
+        indices.dedup();
+        // every variant is covered, the first index past them is unknown
+        let next = *indices.last().unwrap() + 1;
+        assert_eq!(indices, (0..next).collect::<Vec<_>>());
+        assert!(!StudentIntroInstruction::is_variant(next));
+    }
+
+    #[test]
+    fn test_pack_wire_format() {
+        let ix = StudentIntroInstruction::AddStudentIntro {
+            name: "ab".to_owned(),
+            msg: "c".to_owned(),
+        };
+        assert_eq!(ix.pack(), vec![0, 2, 0, 0, 0, b'a', b'b', 1, 0, 0, 0, b'c']);
+        let ix = StudentIntroInstruction::AddReply {
+            reply: "r".to_owned(),
+        };
+        assert_eq!(ix.pack(), vec![2, 1, 0, 0, 0, b'r']);
+        let ix = StudentIntroInstruction::AddReplyWithCounterBump {
+            reply: "r".to_owned(),
+            counter_bump: 7,
+        };
+        assert_eq!(ix.pack(), vec![23, 1, 0, 0, 0, b'r', 7]);
+        assert_eq!(StudentIntroInstruction::InitializeMint.pack(), vec![3]);
+        assert_eq!(StudentIntroInstruction::CloseStudentIntro.pack(), vec![4]);
+    }
+
+    #[test]
+    fn test_unpack_unknown_variant() {
+        assert_eq!(
+            StudentIntroInstruction::unpack(&[255]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+        assert_eq!(
+            StudentIntroInstruction::unpack(&[]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    #[test]
+    fn test_unpack_malformed_payload() {
+        let malformed = ProgramError::from(StudentIntroError::MalformedPayload);
+        // string length prefix larger than the remaining data
+        assert_eq!(
+            StudentIntroInstruction::unpack(&[0, 10, 0, 0, 0, b'a']),
+            Err(malformed.clone())
+        );
+        // missing msg field
+        assert_eq!(
+            StudentIntroInstruction::unpack(&[1, 1, 0, 0, 0, b'a']),
+            Err(malformed.clone())
+        );
+        // invalid utf-8
+        assert_eq!(
+            StudentIntroInstruction::unpack(&[2, 1, 0, 0, 0, 0xff]),
+            Err(malformed.clone())
+        );
+        // trailing bytes
+        assert_eq!(StudentIntroInstruction::unpack(&[3, 0]), Err(malformed));
+    }
 }
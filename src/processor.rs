@@ -1,20 +1,33 @@
 use crate::error::StudentIntroError;
-use crate::instruction::StudentIntroInstruction;
-use crate::state::{StudentIntroAccountState, StudentIntroReply, StudentIntroReplyCounter};
-use borsh::BorshSerialize;
+use crate::events::StudentIntroEvent;
+use crate::instruction::{
+    find_counter_address, find_mint_address, find_mint_auth_address, intro_marker_seeds,
+    intro_seeds, CohortParams, ConfigParams, StudentIntroInstruction,
+};
+use crate::state::{
+    load_account, v0, Cohort, ContentReport, IntroMarker, ProgramAccount, ProgramConfig,
+    ReactionKind, ReplierRecord, ReportReason, RewardTracker, StudentIntroAccountState,
+    StudentIntroReaction, StudentIntroReply, StudentIntroReplyCounter, UserProfile,
+    ACCOUNT_VERSION,
+};
+use crate::validation::{validate_name, validate_name_limits, validate_text};
+use borsh::{BorshDeserialize, BorshSerialize};
+use mpl_token_metadata::{
+    instruction::{create_metadata_accounts_v2, update_metadata_accounts_v2},
+    state::DataV2,
+};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     borsh::try_from_slice_unchecked,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState},
     entrypoint::ProgramResult,
     msg,
-    native_token::LAMPORTS_PER_SOL,
-    program::invoke_signed,
+    program::{invoke, invoke_signed},
     program_error::ProgramError,
-    program_pack::IsInitialized,
     pubkey::Pubkey,
     system_instruction,
     system_program::ID as SYSTEM_PROGRAM_ID,
-    sysvar::{rent::Rent, rent::ID as RENT_PROGRAM_ID, Sysvar},
+    sysvar::{clock::Clock, rent::Rent, rent::ID as RENT_PROGRAM_ID, Sysvar},
 };
 use spl_associated_token_account::get_associated_token_address;
 use spl_token::{
@@ -31,21 +44,75 @@ pub fn process_instruction(
     let instruction = StudentIntroInstruction::unpack(instruction_data)?;
     match instruction {
         StudentIntroInstruction::AddStudentIntro { name, msg } => {
-            add_student_intro(program_id, accounts, name, msg)
+            add_student_intro(program_id, accounts, name, msg, false)
         }
         StudentIntroInstruction::UpdateStudentIntro { name, msg } => {
             update_student_intro(program_id, accounts, name, msg)
         }
-        StudentIntroInstruction::AddReply { reply } => add_reply(program_id, accounts, reply),
+        StudentIntroInstruction::AddReply { reply } => {
+            add_reply(program_id, accounts, reply, None, false)
+        }
         StudentIntroInstruction::InitializeMint => initialize_mint_account(program_id, accounts),
+        StudentIntroInstruction::CloseStudentIntro => close_student_intro(program_id, accounts),
+        StudentIntroInstruction::UpdateReply { reply } => update_reply(program_id, accounts, reply),
+        StudentIntroInstruction::DeleteReply => delete_reply(program_id, accounts),
+        StudentIntroInstruction::InitializeConfig { params } => {
+            initialize_config(program_id, accounts, params)
+        }
+        StudentIntroInstruction::UpdateConfig { admin, params } => {
+            update_config(program_id, accounts, admin, params)
+        }
+        StudentIntroInstruction::CreateMintMetadata { name, symbol, uri } => {
+            create_mint_metadata(program_id, accounts, name, symbol, uri)
+        }
+        StudentIntroInstruction::UpdateMintMetadata { name, symbol, uri } => {
+            update_mint_metadata(program_id, accounts, name, symbol, uri)
+        }
+        StudentIntroInstruction::MigrateAccount => migrate_account(program_id, accounts),
+        StudentIntroInstruction::AddReplyToReply { reply } => {
+            add_reply(program_id, accounts, reply, None, true)
+        }
+        StudentIntroInstruction::React { kind } => react(program_id, accounts, kind),
+        StudentIntroInstruction::Unreact => unreact(program_id, accounts),
+        StudentIntroInstruction::RenameStudentIntro { name, new_name } => {
+            rename_student_intro(program_id, accounts, name, new_name)
+        }
+        StudentIntroInstruction::HideContent => set_hidden(program_id, accounts, true),
+        StudentIntroInstruction::UnhideContent => set_hidden(program_id, accounts, false),
+        StudentIntroInstruction::ForceClose => force_close(program_id, accounts),
+        StudentIntroInstruction::ReportContent { reason } => {
+            report_content(program_id, accounts, reason)
+        }
+        StudentIntroInstruction::CreateCohort {
+            name,
+            admin,
+            params,
+        } => create_cohort(program_id, accounts, name, admin, params),
+        StudentIntroInstruction::UpdateCohort { admin, params } => {
+            update_cohort(program_id, accounts, admin, params)
+        }
+        StudentIntroInstruction::AddCohortIntro { name, msg } => {
+            add_student_intro(program_id, accounts, name, msg, true)
+        }
+        StudentIntroInstruction::AddReplyWithCounterBump {
+            reply,
+            counter_bump,
+        } => add_reply(program_id, accounts, reply, Some(counter_bump), false),
+        StudentIntroInstruction::AddReplyToReplyWithCounterBump {
+            reply,
+            counter_bump,
+        } => add_reply(program_id, accounts, reply, Some(counter_bump), true),
     }
 }
 
+/// Posts an intro, in the cohort passed after the other accounts when
+/// `in_cohort` is set.
 pub fn add_student_intro(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     name: String,
     msg: String,
+    in_cohort: bool,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -57,22 +124,41 @@ pub fn add_student_intro(
     let user_ata = next_account_info(account_info_iter)?; //initializer's associated token acc
     let system_program = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let tracker_account = next_account_info(account_info_iter)?;
+    let profile_account = next_account_info(account_info_iter)?;
+    let marker_account = next_account_info(account_info_iter)?;
+    let cohort_account = if in_cohort {
+        Some(next_account_info(account_info_iter)?)
+    } else {
+        None
+    };
 
     if !initializer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
+    let config = load_config(program_id, config_account)?;
+    validate_name(&name, &config.name_limits)?;
+    validate_text(&msg, &config.msg_limits)?;
+    let cohort = cohort_account
+        .map(|cohort_account| load_open_cohort(program_id, cohort_account))
+        .transpose()?;
+    let cohort_key = cohort_account.map(|cohort_account| cohort_account.key);
 
-    let (pda, bump) = Pubkey::find_program_address(
-        &[initializer.key.as_ref(), name.as_bytes().as_ref()],
-        program_id,
-    );
-    let (mint_pda, _mint_bump) = Pubkey::find_program_address(&[b"token_mint"], program_id);
-    let (mint_auth_pda, mint_auth_bump) =
-        Pubkey::find_program_address(&[b"token_auth"], program_id);
+    let seeds = intro_seeds(cohort_key, initializer.key, &name);
+    let (pda, bump) = Pubkey::find_program_address(&seeds, program_id);
+    let mint_pda = create_pda(program_id, &[b"token_mint"], config.mint_bump)?;
+    let mint_auth_pda = create_pda(program_id, &[b"token_auth"], config.mint_auth_bump)?;
     msg!("Found PDA: {}", pda);
     if pda != *pda_account.key {
         return Err(StudentIntroError::InvalidPda.into());
     }
+    let (counter_pda, counter_bump) =
+        Pubkey::find_program_address(&[pda.as_ref(), "reply".as_ref()], program_id);
+    if counter_pda != *counter_account.key {
+        msg!("invalid seeds for counter PDA");
+        return Err(ProgramError::InvalidArgument);
+    }
     if mint_pda != *mint_account.key {
         return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
@@ -88,16 +174,24 @@ pub fn add_student_intro(
     if SYSTEM_PROGRAM_ID != *system_program.key {
         return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
-    let data_len = 1000;
-    if StudentIntroAccountState::get_account_size(name.clone(), msg.clone()) > data_len {
+    let data_len = StudentIntroAccountState::get_account_size(
+        name.clone(),
+        msg.clone(),
+        None,
+        cohort_key.copied(),
+    );
+    if data_len > config.max_intro_size as usize {
         return Err(StudentIntroError::InvalidDataLength.into());
     }
     let rent_amt = Rent::get()?.minimum_balance(data_len);
 
+    let bump_seed = [bump];
+    let mut signer_seeds = seeds;
+    signer_seeds.push(&bump_seed);
     invoke_signed(
         &system_instruction::create_account(
-            &initializer.key,
-            &pda_account.key,
+            initializer.key,
+            pda_account.key,
             rent_amt,
             data_len.try_into().unwrap(),
             program_id,
@@ -107,66 +201,114 @@ pub fn add_student_intro(
             pda_account.clone(),
             system_program.clone(),
         ],
-        &[&[initializer.key.as_ref(), name.as_bytes().as_ref(), &[bump]]],
+        &[&signer_seeds],
     )?;
     msg!("Created PDA account successfully");
-    msg!("Deserializing account data");
+    mark_intro(
+        program_id,
+        initializer,
+        marker_account,
+        system_program,
+        &pda,
+        cohort_key,
+        config.one_intro_per_wallet,
+    )?;
     msg!("Name: {}", name.clone());
     msg!("Msg: {}", msg.clone());
-    let mut account_data =
-        try_from_slice_unchecked::<StudentIntroAccountState>(&pda_account.data.borrow()).unwrap();
-    if account_data.is_initialized() {
-        msg!("PDA account already initialized");
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
-    account_data.discriminator = StudentIntroAccountState::DISCRIMINATOR.to_string();
-    account_data.identity = *initializer.key;
-    account_data.name = name;
-    account_data.msg = msg;
-    account_data.is_initialized = true;
+    let account_data = StudentIntroAccountState {
+        discriminator: StudentIntroAccountState::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        hidden: false,
+        report_count: 0,
+        bump,
+        counter_bump,
+        reaction_counts: [0; ReactionKind::COUNT],
+        identity: *initializer.key,
+        name,
+        msg,
+        origin: None,
+        cohort: cohort_key.copied(),
+        created_slot: Clock::get()?.slot,
+    };
     msg!("Serializing account data");
     account_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
     msg!("Serialization successful");
+    StudentIntroEvent::IntroCreated {
+        intro: pda,
+        identity: account_data.identity,
+        name: account_data.name.clone(),
+        msg: account_data.msg,
+    }
+    .emit();
 
-    let (counter_pda, counter_bump) =
-        Pubkey::find_program_address(&[pda.as_ref(), "reply".as_ref()], program_id);
-    if counter_pda != *counter_account.key {
-        msg!("invalid seeds for counter PDA");
-        return Err(ProgramError::InvalidArgument);
+    let counter_data = if counter_account.data_is_empty() {
+        let rent_amt = Rent::get()?.minimum_balance(StudentIntroReplyCounter::SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                initializer.key,
+                counter_account.key,
+                rent_amt,
+                StudentIntroReplyCounter::SIZE.try_into().unwrap(),
+                program_id,
+            ),
+            &[
+                initializer.clone(),
+                counter_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[pda.as_ref(), "reply".as_ref(), &[counter_bump]]],
+        )?;
+        msg!("created counter PDA");
+        StudentIntroReplyCounter {
+            discriminator: StudentIntroReplyCounter::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            counter: 0,
+        }
+    } else {
+        // a closed intro left its counter behind, its replies keep their seeds
+        let counter = load_detached_counter(program_id, counter_account)?;
+        msg!("took over counter PDA at {}", counter.counter);
+        StudentIntroReplyCounter {
+            is_initialized: true,
+            ..counter
+        }
+    };
+    counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+    msg!("serialized counter PDA");
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut tracker =
+        load_reward_tracker(program_id, initializer, tracker_account, system_program)?;
+    let intro_reward = cohort
+        .and_then(|cohort| cohort.intro_reward)
+        .unwrap_or(config.intro_reward);
+    let reward = tracker.take(intro_reward, config.daily_reward_cap, now);
+    tracker.serialize(&mut &mut tracker_account.data.borrow_mut()[..])?;
+
+    let mut profile = load_user_profile(program_id, initializer, profile_account, now)?;
+    if profile.display_name.is_empty() {
+        profile.display_name = account_data.name;
     }
-    let rent_amt = Rent::get()?.minimum_balance(StudentIntroReplyCounter::SIZE);
-    invoke_signed(
-        &system_instruction::create_account(
-            initializer.key,
-            counter_account.key,
-            rent_amt,
-            StudentIntroReplyCounter::SIZE.try_into().unwrap(),
-            program_id,
-        ),
-        &[
-            initializer.clone(),
-            counter_account.clone(),
-            system_program.clone(),
-        ],
-        &[&[pda.as_ref(), "reply".as_ref(), &[counter_bump]]],
+    profile.total_intros += 1;
+    profile.total_tokens_earned += reward;
+    store_user_profile(
+        program_id,
+        initializer,
+        profile_account,
+        system_program,
+        &profile,
     )?;
-    msg!("created counter PDA");
-    let mut counter_data =
-        try_from_slice_unchecked::<StudentIntroReplyCounter>(&counter_account.data.borrow())
-            .unwrap();
-    if counter_data.is_initialized() {
-        msg!("counter PDA already initialized");
-        return Err(ProgramError::AccountAlreadyInitialized);
+    if reward == 0 {
+        msg!("daily reward cap reached, no tokens minted");
+        return Ok(());
     }
-    counter_data.discriminator = StudentIntroReplyCounter::DISCRIMINATOR.to_string();
-    counter_data.is_initialized = true;
-    counter_data.counter = 0;
-    counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
-    msg!("serialized counter PDA");
 
     //token mint logic
     msg!(
-        "minting 10 tokens({:?}) to {:?}",
+        "minting {} tokens({:?}) to {:?}",
+        reward,
         mint_account.key,
         initializer.key
     );
@@ -177,12 +319,17 @@ pub fn add_student_intro(
             user_ata.key,
             mint_auth.key,
             &[],
-            10 * LAMPORTS_PER_SOL, //our token has 9 decimals, same as SOL
+            reward,
         )?,
         &[mint_account.clone(), user_ata.clone(), mint_auth.clone()],
-        &[&[b"token_auth", &[mint_auth_bump]]],
+        &[&[b"token_auth", &[config.mint_auth_bump]]],
     )?;
     msg!("token mint successful");
+    StudentIntroEvent::TokensMinted {
+        recipient: *initializer.key,
+        amount: reward,
+    }
+    .emit();
     Ok(())
 }
 
@@ -195,45 +342,75 @@ pub fn update_student_intro(
     let account_info_iter = &mut accounts.iter();
     let initializer = next_account_info(account_info_iter)?;
     let pda_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
     if !initializer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
-    if pda_account.owner != program_id {
-        return Err(ProgramError::IllegalOwner);
+    let config = load_config(program_id, config_account)?;
+    validate_text(&msg, &config.msg_limits)?;
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
     msg!("Deserializing account data");
-    let mut account_data =
-        try_from_slice_unchecked::<StudentIntroAccountState>(&pda_account.data.borrow()).unwrap();
-    let (pda, _bump) = Pubkey::find_program_address(
-        &[
-            initializer.key.as_ref(),
-            account_data.name.as_bytes().as_ref(),
-        ],
+    let mut account_data = load_account::<StudentIntroAccountState>(pda_account, program_id)?;
+    let pda = create_pda(
         program_id,
-    );
+        &intro_seeds(
+            account_data.cohort.as_ref(),
+            initializer.key,
+            &account_data.name,
+        ),
+        account_data.bump,
+    )?;
     if pda != *pda_account.key {
         return Err(StudentIntroError::InvalidPda.into());
     }
-    if !account_data.is_initialized() {
-        return Err(StudentIntroError::UninitializedAccount.into());
-    }
     if account_data.name != name {
         return Err(StudentIntroError::InvalidStudentName.into());
     }
-    if 1 + 4 + name.len() + 4 + msg.len() > 1000 {
+    let data_len = StudentIntroAccountState::get_account_size(
+        name,
+        msg.clone(),
+        account_data.origin,
+        account_data.cohort,
+    );
+    if data_len > config.max_intro_size as usize {
         return Err(StudentIntroError::InvalidDataLength.into());
     }
+    msg!("Resizing PDA account to {} bytes", data_len);
+    resize_account(
+        pda_account,
+        initializer,
+        Some(initializer),
+        system_program,
+        data_len,
+    )?;
     account_data.msg = msg;
     msg!("Serializing account data");
     account_data.serialize(&mut &mut pda_account.data.borrow_mut()[..])?;
+    StudentIntroEvent::IntroUpdated {
+        intro: pda,
+        msg: account_data.msg,
+    }
+    .emit();
     Ok(())
 }
 
-pub fn add_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -> ProgramResult {
+/// Posts a reply under an intro, or under another reply when `to_reply` is set.
+/// `reply_counter_bump` is the canonical bump of the new reply's counter PDA,
+/// searched for when not given.
+pub fn add_reply(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reply: String,
+    reply_counter_bump: Option<u8>,
+    to_reply: bool,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
     let replier = next_account_info(account_info_iter)?;
-    let intro_account = next_account_info(account_info_iter)?;
+    let parent_account = next_account_info(account_info_iter)?; //intro, or reply when to_reply
     let counter_account = next_account_info(account_info_iter)?;
     let reply_account = next_account_info(account_info_iter)?;
     let mint_account = next_account_info(account_info_iter)?;
@@ -241,54 +418,157 @@ pub fn add_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -
     let user_ata = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let record_account = next_account_info(account_info_iter)?;
+    let tracker_account = next_account_info(account_info_iter)?;
+    let profile_account = next_account_info(account_info_iter)?;
 
     if !replier.is_signer {
         msg!("replier needs to sign the txn");
         return Err(ProgramError::MissingRequiredSignature);
     }
-    if intro_account.owner != program_id {
-        msg!("PDA account not owned by program");
-        return Err(ProgramError::IllegalOwner);
-    }
-    let intro_data =
-        try_from_slice_unchecked::<StudentIntroAccountState>(&intro_account.data.borrow()).unwrap();
-    if !intro_data.is_initialized() {
-        msg!("PDA account not initialized");
-        return Err(StudentIntroError::UninitializedAccount.into());
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
-    let (pda, _) = Pubkey::find_program_address(
-        &[intro_data.identity.as_ref(), intro_data.name.as_ref()],
-        program_id,
-    );
-    if pda != *intro_account.key {
+    let config = load_config(program_id, config_account)?;
+    validate_text(&reply, &config.reply_limits)?;
+    let (pda, intro, parent_reply, counter_bump, parent_author, cohort_key) = if to_reply {
+        let parent_data = load_account::<StudentIntroReply>(parent_account, program_id)?;
+        if parent_data.hidden {
+            return Err(StudentIntroError::ContentHidden.into());
+        }
+        // hiding an intro closes its whole thread
+        let intro_account = next_account_info(account_info_iter)?;
+        let intro_data = load_account::<StudentIntroAccountState>(intro_account, program_id)?;
+        let intro_pda = create_pda(
+            program_id,
+            &intro_seeds(
+                intro_data.cohort.as_ref(),
+                &intro_data.identity,
+                &intro_data.name,
+            ),
+            intro_data.bump,
+        )?;
+        if intro_pda != *intro_account.key || *intro_data.thread(&intro_pda) != parent_data.intro {
+            msg!("thread's intro is {}", parent_data.intro);
+            return Err(StudentIntroError::IncorrectAccountPassed.into());
+        }
+        if intro_data.hidden {
+            return Err(StudentIntroError::ContentHidden.into());
+        }
+        let pda = create_pda(
+            program_id,
+            &[
+                parent_data.parent().as_ref(),
+                parent_data.count.to_be_bytes().as_ref(),
+            ],
+            parent_data.bump,
+        )?;
+        (
+            pda,
+            parent_data.intro,
+            Some(pda),
+            parent_data.counter_bump,
+            parent_data.replier,
+            parent_data.cohort,
+        )
+    } else {
+        let intro_data = load_account::<StudentIntroAccountState>(parent_account, program_id)?;
+        if intro_data.hidden {
+            return Err(StudentIntroError::ContentHidden.into());
+        }
+        let pda = create_pda(
+            program_id,
+            &intro_seeds(
+                intro_data.cohort.as_ref(),
+                &intro_data.identity,
+                &intro_data.name,
+            ),
+            intro_data.bump,
+        )?;
+        (
+            pda,
+            *intro_data.thread(&pda),
+            None,
+            intro_data.counter_bump,
+            intro_data.identity,
+            intro_data.cohort,
+        )
+    };
+    if pda != *parent_account.key {
         msg!("Invalid PDA account passed");
         return Err(ProgramError::InvalidArgument);
     }
-    if counter_account.owner != program_id {
-        msg!("counter account not owned by program");
-        return Err(ProgramError::IllegalOwner);
-    }
-    let (counter_pda, _) =
-        Pubkey::find_program_address(&[pda.as_ref(), "reply".as_ref()], program_id);
+    // replies in a cohort's thread take the cohort after the other accounts
+    let cohort = match cohort_key {
+        Some(cohort_key) => {
+            let cohort_account = next_account_info(account_info_iter)?;
+            if *cohort_account.key != cohort_key {
+                msg!("thread belongs to cohort {}", cohort_key);
+                return Err(StudentIntroError::IncorrectAccountPassed.into());
+            }
+            Some(load_open_cohort(program_id, cohort_account)?)
+        }
+        None => None,
+    };
+    // replies of renamed intros stay seeded under the intro's origin
+    let parent = parent_reply.unwrap_or(intro);
+    let counter_pda = create_pda(
+        program_id,
+        &[parent.as_ref(), "reply".as_ref()],
+        counter_bump,
+    )?;
     if counter_pda != *counter_account.key {
         msg!("Invalid counter account passed");
         return Err(ProgramError::InvalidArgument);
     }
-    let mut counter_data =
-        try_from_slice_unchecked::<StudentIntroReplyCounter>(&counter_account.data.borrow())
-            .unwrap();
+    let mut counter_data = if to_reply && counter_account.data_is_empty() {
+        // reply counters are created with the first reply to a reply
+        let rent_amt = Rent::get()?.minimum_balance(StudentIntroReplyCounter::SIZE);
+        invoke_signed(
+            &system_instruction::create_account(
+                replier.key,
+                counter_account.key,
+                rent_amt,
+                StudentIntroReplyCounter::SIZE.try_into().unwrap(),
+                program_id,
+            ),
+            &[
+                replier.clone(),
+                counter_account.clone(),
+                system_program.clone(),
+            ],
+            &[&[parent.as_ref(), "reply".as_ref(), &[counter_bump]]],
+        )?;
+        msg!("created counter PDA");
+        StudentIntroReplyCounter {
+            discriminator: StudentIntroReplyCounter::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            counter: 0,
+        }
+    } else {
+        load_account::<StudentIntroReplyCounter>(counter_account, program_id)?
+    };
     let reply_count = counter_data.counter;
     let (reply_pda, reply_bump) = Pubkey::find_program_address(
-        &[pda.as_ref(), reply_count.to_be_bytes().as_ref()],
+        &[parent.as_ref(), reply_count.to_be_bytes().as_ref()],
         program_id,
     );
     if reply_pda != *reply_account.key {
         msg!("Invalid seeds for reply PDA");
         return Err(StudentIntroError::InvalidPda.into());
     }
-    let (mint_pda, _mint_bump) = Pubkey::find_program_address(&[b"token_mint"], program_id);
-    let (mint_auth_pda, mint_auth_bump) =
-        Pubkey::find_program_address(&[b"token_auth"], program_id);
+    // the reply's own counter is only created with its first reply
+    let reply_counter_bump = match reply_counter_bump {
+        Some(bump) => {
+            create_canonical_pda(program_id, &[reply_pda.as_ref(), "reply".as_ref()], bump)?;
+            bump
+        }
+        None => find_counter_address(program_id, &reply_pda).1,
+    };
+    let mint_pda = create_pda(program_id, &[b"token_mint"], config.mint_bump)?;
+    let mint_auth_pda = create_pda(program_id, &[b"token_auth"], config.mint_auth_bump)?;
     if mint_pda != *mint_account.key {
         return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
@@ -301,12 +581,9 @@ pub fn add_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -
     if TOKEN_PROGRAM_ID != *token_program.key {
         return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
-    if SYSTEM_PROGRAM_ID != *system_program.key {
-        return Err(StudentIntroError::IncorrectAccountPassed.into());
-    }
 
     msg!("found reply PDA: {}", reply_pda);
-    let account_size = StudentIntroReply::get_account_size(reply.clone());
+    let account_size = StudentIntroReply::get_account_size(reply.clone(), parent_reply, cohort_key);
     let rent_amt = Rent::get()?.minimum_balance(account_size);
     invoke_signed(
         &system_instruction::create_account(
@@ -322,33 +599,102 @@ pub fn add_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -
             system_program.clone(),
         ],
         &[&[
-            pda.as_ref(),
+            parent.as_ref(),
             reply_count.to_be_bytes().as_ref(),
             &[reply_bump],
         ]],
     )?;
     msg!("created reply PDA account");
-    let mut reply_data =
-        try_from_slice_unchecked::<StudentIntroReply>(&reply_account.data.borrow()).unwrap();
-    if reply_data.is_initialized() {
-        msg!("reply account already initialized");
-        return Err(ProgramError::AccountAlreadyInitialized);
-    }
-    reply_data.discriminator = StudentIntroReply::DISCRIMINATOR.to_string();
-    reply_data.is_initialized = true;
-    reply_data.intro = *intro_account.key;
-    reply_data.replier = *replier.key;
-    reply_data.reply = reply;
-    reply_data.count = reply_count;
+    let reply_data = StudentIntroReply {
+        discriminator: StudentIntroReply::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        hidden: false,
+        report_count: 0,
+        bump: reply_bump,
+        counter_bump: reply_counter_bump,
+        reaction_counts: [0; ReactionKind::COUNT],
+        intro,
+        parent_reply,
+        replier: *replier.key,
+        reply,
+        count: reply_count,
+        cohort: cohort_key,
+    };
     reply_data.serialize(&mut &mut reply_account.data.borrow_mut()[..])?;
     msg!("serialized reply PDA");
     counter_data.counter += 1;
+    if counter_account.data_len() != StudentIntroReplyCounter::SIZE {
+        // counters written with an older layout are upgraded on write
+        resize_account(
+            counter_account,
+            replier,
+            None,
+            system_program,
+            StudentIntroReplyCounter::SIZE,
+        )?;
+    }
     counter_data.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
     msg!("serialized counter PDA");
+    match parent_reply {
+        Some(parent_reply) => StudentIntroEvent::ReplyToReplyAdded {
+            intro,
+            parent_reply,
+            reply: reply_pda,
+            replier: *replier.key,
+            count: reply_count,
+        },
+        None => StudentIntroEvent::ReplyAdded {
+            intro,
+            reply: reply_pda,
+            replier: *replier.key,
+            count: reply_count,
+        },
+    }
+    .emit();
+
+    let now = Clock::get()?.unix_timestamp;
+    let mut record =
+        load_replier_record(program_id, &intro, replier, record_account, system_program)?;
+    let mut tracker = load_reward_tracker(program_id, replier, tracker_account, system_program)?;
+    let reward = if parent_author == *replier.key && !config.reward_self_replies {
+        msg!("self replies are not rewarded");
+        0
+    } else if record.rewarded_replies >= config.max_rewarded_replies_per_intro {
+        msg!("rewarded replies limit reached for this intro");
+        0
+    } else {
+        let reply_reward = cohort
+            .and_then(|cohort| cohort.reply_reward)
+            .unwrap_or(config.reply_reward);
+        let reward = tracker.take(reply_reward, config.daily_reward_cap, now);
+        if reward > 0 {
+            record.rewarded_replies += 1;
+        }
+        reward
+    };
+    record.serialize(&mut &mut record_account.data.borrow_mut()[..])?;
+    tracker.serialize(&mut &mut tracker_account.data.borrow_mut()[..])?;
+
+    let mut profile = load_user_profile(program_id, replier, profile_account, now)?;
+    profile.total_replies += 1;
+    profile.total_tokens_earned += reward;
+    store_user_profile(
+        program_id,
+        replier,
+        profile_account,
+        system_program,
+        &profile,
+    )?;
+    if reward == 0 {
+        msg!("no tokens minted for this reply");
+        return Ok(());
+    }
 
     //token mint logic
     msg!(
-        "minting 5 tokens{:?} to {:?}",
+        "minting {} tokens{:?} to {:?}",
+        reward,
         mint_account.key,
         replier.key
     );
@@ -359,12 +705,17 @@ pub fn add_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -
             user_ata.key,
             mint_auth.key,
             &[],
-            5 * LAMPORTS_PER_SOL,
+            reward,
         )?,
         &[mint_account.clone(), user_ata.clone(), mint_auth.clone()],
-        &[&[b"token_auth", &[mint_auth_bump]]],
+        &[&[b"token_auth", &[config.mint_auth_bump]]],
     )?;
-    msg!("successfully minted 5 tokens to replier");
+    msg!("successfully minted {} tokens to replier", reward);
+    StudentIntroEvent::TokensMinted {
+        recipient: *replier.key,
+        amount: reward,
+    }
+    .emit();
     Ok(())
 }
 
@@ -377,13 +728,18 @@ pub fn initialize_mint_account(program_id: &Pubkey, accounts: &[AccountInfo]) ->
     let system_program = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
     let sysvar_rent = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
 
     if !initializer.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
     }
-    let (mint_pda, mint_bump) = Pubkey::find_program_address(&[b"token_mint"], program_id);
-    let (mint_auth_pda, _mint_auth_bump) =
-        Pubkey::find_program_address(&[b"token_auth"], program_id);
+    let config = load_config(program_id, config_account)?;
+    if config.admin != *initializer.key {
+        msg!("only the config admin can initialize the mint");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let mint_pda = create_pda(program_id, &[b"token_mint"], config.mint_bump)?;
+    let mint_auth_pda = create_pda(program_id, &[b"token_auth"], config.mint_auth_bump)?;
     if mint_pda != *mint_account.key || mint_auth_pda != *mint_auth.key {
         return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
@@ -412,7 +768,7 @@ pub fn initialize_mint_account(program_id: &Pubkey, accounts: &[AccountInfo]) ->
             mint_account.clone(),
             system_program.clone(),
         ],
-        &[&[b"token_mint", &[mint_bump]]],
+        &[&[b"token_mint", &[config.mint_bump]]],
     )?;
     msg!("created mint account successfully");
     msg!("initializing mint account");
@@ -422,274 +778,3078 @@ pub fn initialize_mint_account(program_id: &Pubkey, accounts: &[AccountInfo]) ->
             mint_account.key,
             mint_auth.key,
             Option::None,
-            9,
+            config.mint_decimals,
         )?,
         &[mint_account.clone(), sysvar_rent.clone(), mint_auth.clone()],
-        &[&[b"token_mint", &[mint_bump]]],
+        &[&[b"token_mint", &[config.mint_bump]]],
     )?;
     msg!("initialized mint account successfully");
+    StudentIntroEvent::MintInitialized {
+        mint: mint_pda,
+        decimals: config.mint_decimals,
+    }
+    .emit();
 
     Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use borsh::BorshDeserialize;
-    use solana_program::{
-        instruction::{AccountMeta, Instruction},
-        system_program::ID as SYSTEM_PROGRAM_ID,
-    };
-    use spl_associated_token_account::{
-        get_associated_token_address, instruction::create_associated_token_account,
-    };
-    use spl_token::ID as TOKEN_PROGRAM_ID;
-    use std::borrow::Borrow;
-    use {
-        assert_matches::*,
-        solana_program_test::*,
-        solana_sdk::{
-            signature::Signer, sysvar::rent::ID as SYSVAR_RENT_ID, transaction::Transaction,
-        },
-    };
-
-    fn create_initialize_mint_ix(
-        initializer: Pubkey,
-        program_id: Pubkey,
-    ) -> (Pubkey, Pubkey, Instruction) {
-        let (mint, _mint_bump) = Pubkey::find_program_address(&[b"token_mint"], &program_id);
-        let (mint_auth, _mint_auth_bump) =
-            Pubkey::find_program_address(&[b"token_auth"], &program_id);
-        let ix = Instruction {
-            program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(initializer, true),
-                AccountMeta::new(mint, false),
-                AccountMeta::new(mint_auth, false),
-                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-                AccountMeta::new_readonly(SYSVAR_RENT_ID, false),
-            ],
-            data: vec![3],
-        };
-        (mint, mint_auth, ix)
-    }
-    fn create_add_intro_ix(
-        payer: Pubkey,
-        program_id: Pubkey,
-        name: String,
-        msg: String,
-    ) -> Instruction {
-        let (mint, _mint_bump) = Pubkey::find_program_address(&[b"token_mint"], &program_id);
-        let (mint_auth, _mint_auth_bump) =
-            Pubkey::find_program_address(&[b"token_auth"], &program_id);
-        let (intro_pda, _intro_bump) =
-            Pubkey::find_program_address(&[payer.as_ref(), name.as_ref()], &program_id);
-        let (counter_pda, _counter_bump) =
-            Pubkey::find_program_address(&[intro_pda.as_ref(), b"reply"], &program_id);
-        let ata = get_associated_token_address(&payer, &mint);
-        let mut data = vec![0];
-        data.append(
-            &mut (TryInto::<u32>::try_into(name.len()).unwrap().to_le_bytes())
-                .try_into()
-                .unwrap(),
-        );
-        data.append(&mut name.into_bytes());
-        data.append(
-            &mut (TryInto::<u32>::try_into(msg.len()).unwrap().to_le_bytes())
-                .try_into()
-                .unwrap(),
-        );
-        data.append(&mut msg.into_bytes());
-        let accounts = vec![
-            AccountMeta::new_readonly(payer, true),
-            AccountMeta::new(intro_pda, false),
-            AccountMeta::new(counter_pda, false),
-            AccountMeta::new(mint, false),
-            AccountMeta::new_readonly(mint_auth, false),
-            AccountMeta::new(ata, false),
-            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-            AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
-        ];
-        Instruction {
-            program_id,
-            accounts,
-            data,
-        }
-    }
+pub fn close_student_intro(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
 
-    #[tokio::test]
-    async fn test_init_mint_acc_ix() {
-        let program_id = Pubkey::new_unique();
-        let (mut banks_client, payer, recent_blockhash) = ProgramTest::new(
-            "solana_student_intro_reply",
-            program_id,
-            processor!(process_instruction),
-        )
-        .start()
-        .await;
+    let initializer = next_account_info(account_info_iter)?;
+    let pda_account = next_account_info(account_info_iter)?;
+    let counter_account = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
 
-        let (_mint, _mint_auth, ix) = create_initialize_mint_ix(payer.pubkey(), program_id);
-        let mut tx = Transaction::new_with_payer(&[ix], Some(&payer.pubkey()));
-        tx.sign(&[&payer], recent_blockhash);
-        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+    if !initializer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
     }
-
-    #[tokio::test]
-    async fn test_add_student_intro_ix() {
-        let program_id = Pubkey::new_unique();
-        let (mut banks_client, payer, recent_blockhash) = ProgramTest::new(
-            "solana_student_intro_reply",
-            program_id,
-            processor!(process_instruction),
-        )
-        .start()
-        .await;
-        let name = "Naruto".to_owned();
-        let msg = "Developing solana jutsu".to_owned();
-        let (mint, _mint_auth, init_mint_ix) =
-            create_initialize_mint_ix(payer.pubkey(), program_id);
-        let create_ata_ix =
-            create_associated_token_account(&payer.pubkey(), &payer.pubkey(), &mint);
-        let add_intro_ix = create_add_intro_ix(payer.pubkey(), program_id, name, msg);
-        let mut tx = Transaction::new_with_payer(
-            &[init_mint_ix, create_ata_ix, add_intro_ix],
-            Some(&payer.pubkey()),
-        );
-        tx.sign(&[&payer], recent_blockhash);
-        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+    let account_data = load_account::<StudentIntroAccountState>(pda_account, program_id)?;
+    let counter_data = load_account::<StudentIntroReplyCounter>(counter_account, program_id)?;
+    let pda = create_pda(
+        program_id,
+        &intro_seeds(
+            account_data.cohort.as_ref(),
+            initializer.key,
+            &account_data.name,
+        ),
+        account_data.bump,
+    )?;
+    if pda != *pda_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
     }
-
-    #[tokio::test]
-    async fn test_update_student_intro_ix() {
-        let program_id = Pubkey::new_unique();
-        let (mut banks_client, payer, recent_blockhash) = ProgramTest::new(
-            "solana_student_intro_reply",
-            program_id,
-            processor!(process_instruction),
-        )
-        .start()
-        .await;
-        let name = "Naruto".to_owned();
-        let (prev_msg, new_msg) = (
-            "Looking to develop solana jutsu".to_owned(),
-            "Exploring solana ecosystem".to_owned(),
-        );
-        let (mint, _mint_auth, init_mint_ix) =
-            create_initialize_mint_ix(payer.pubkey(), program_id);
-        let create_ata_ix =
-            create_associated_token_account(&payer.pubkey(), &payer.pubkey(), &mint);
-        let add_intro_ix = create_add_intro_ix(payer.pubkey(), program_id, name.clone(), prev_msg);
-        let (intro_pda, _intro_bump) =
-            Pubkey::find_program_address(&[payer.pubkey().as_ref(), name.as_ref()], &program_id);
-        let mut data = vec![1];
-        data.append(
-            &mut (TryInto::<u32>::try_into(name.len()).unwrap().to_le_bytes())
-                .try_into()
-                .unwrap(),
-        );
-        data.append(&mut name.into_bytes());
-        data.append(
-            &mut (TryInto::<u32>::try_into(new_msg.len())
-                .unwrap()
-                .to_le_bytes())
-            .try_into()
-            .unwrap(),
-        );
-        data.append(&mut new_msg.into_bytes());
-        let update_intro_ix = Instruction {
-            program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(payer.pubkey(), true),
-                AccountMeta::new(intro_pda, false),
-            ],
-            data,
-        };
-        let mut tx = Transaction::new_with_payer(
-            &[init_mint_ix, create_ata_ix, add_intro_ix, update_intro_ix],
-            Some(&payer.pubkey()),
-        );
-        tx.sign(&[&payer], recent_blockhash);
-        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+    let counter_pda = create_pda(
+        program_id,
+        &[account_data.thread(&pda).as_ref(), "reply".as_ref()],
+        account_data.counter_bump,
+    )?;
+    if counter_pda != *counter_account.key {
+        msg!("invalid seeds for counter PDA");
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if *destination.key == pda || *destination.key == counter_pda {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
     }
 
-    #[tokio::test]
-    async fn test_add_reply_ix() {
-        let program_id = Pubkey::new_unique();
-        let (mut banks_client, payer, recent_blockhash) = ProgramTest::new(
-            "solana_student_intro_reply",
-            program_id,
-            processor!(process_instruction),
-        )
-        .start()
-        .await;
-        let name = "Naruto".to_owned();
-        let msg = "Looking to develop solana jutsu".to_owned();
-        let reply = "All the best Naruto".to_owned();
+    close_intro_counter(pda_account, counter_account, counter_data, destination)?;
+    msg!("closing intro PDA: {}", pda);
+    close_account(pda_account, destination)?;
+    StudentIntroEvent::IntroClosed { intro: pda }.emit();
+    Ok(())
+}
 
-        let (mint, mint_auth, init_mint_ix) = create_initialize_mint_ix(payer.pubkey(), program_id);
-        let create_ata_ix =
-            create_associated_token_account(&payer.pubkey(), &payer.pubkey(), &mint);
-        let add_intro_ix = create_add_intro_ix(payer.pubkey(), program_id, name.clone(), msg);
+/// Moves an intro to the PDA of `new_name`, keeping its replies under its origin.
+pub fn rename_student_intro(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    new_name: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
 
-        let mut tx1 = Transaction::new_with_payer(
-            &[init_mint_ix, create_ata_ix, add_intro_ix],
-            Some(&payer.pubkey()),
-        );
-        tx1.sign(&[&payer], recent_blockhash);
-        banks_client.process_transaction(tx1).await.unwrap();
+    let initializer = next_account_info(account_info_iter)?;
+    let pda_account = next_account_info(account_info_iter)?;
+    let new_pda_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let marker_account = next_account_info(account_info_iter)?;
 
-        let (intro_pda, _intro_bump) =
-            Pubkey::find_program_address(&[payer.pubkey().as_ref(), name.as_ref()], &program_id);
-        let (counter_pda, _counter_bump) =
-            Pubkey::find_program_address(&[intro_pda.as_ref(), b"reply"], &program_id);
-        let mut counter_acc = banks_client
-            .get_account(counter_pda)
-            .await
-            .unwrap()
-            .unwrap();
-        let mut counter =
-            StudentIntroReplyCounter::try_from_slice(&counter_acc.data.borrow()).unwrap();
-        assert!(counter.counter == 0);
-        let (reply_pda, _reply_bump) = Pubkey::find_program_address(
-            &[intro_pda.as_ref(), counter.counter.to_be_bytes().as_ref()],
-            &program_id,
+    if !initializer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let config = load_config(program_id, config_account)?;
+    validate_name(&new_name, &config.name_limits)?;
+    let account_data = load_account::<StudentIntroAccountState>(pda_account, program_id)?;
+    let pda = create_pda(
+        program_id,
+        &intro_seeds(
+            account_data.cohort.as_ref(),
+            initializer.key,
+            &account_data.name,
+        ),
+        account_data.bump,
+    )?;
+    if pda != *pda_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if account_data.name != name || new_name == name {
+        return Err(StudentIntroError::InvalidStudentName.into());
+    }
+    // renamed intros stay in their cohort
+    let new_seeds = intro_seeds(account_data.cohort.as_ref(), initializer.key, &new_name);
+    let (new_pda, bump) = Pubkey::find_program_address(&new_seeds, program_id);
+    if new_pda != *new_pda_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    let origin = *account_data.thread(&pda);
+    let data_len = StudentIntroAccountState::get_account_size(
+        new_name.clone(),
+        account_data.msg.clone(),
+        Some(origin),
+        account_data.cohort,
+    );
+    if data_len > config.max_intro_size as usize {
+        return Err(StudentIntroError::InvalidDataLength.into());
+    }
+    let bump_seed = [bump];
+    let mut signer_seeds = new_seeds;
+    signer_seeds.push(&bump_seed);
+    create_pda_account(
+        program_id,
+        initializer,
+        new_pda_account,
+        system_program,
+        data_len,
+        &signer_seeds,
+    )?;
+    let new_data = StudentIntroAccountState {
+        version: ACCOUNT_VERSION,
+        bump,
+        name: new_name,
+        origin: Some(origin),
+        ..account_data
+    };
+    new_data.serialize(&mut &mut new_pda_account.data.borrow_mut()[..])?;
+    msg!("moved intro {} to {}", pda, new_pda);
+    close_account(pda_account, initializer)?;
+    move_intro_marker(
+        program_id,
+        initializer.key,
+        marker_account,
+        new_data.cohort.as_ref(),
+        &pda,
+        &new_pda,
+    )?;
+    StudentIntroEvent::IntroRenamed {
+        intro: new_pda,
+        previous: pda,
+        origin,
+        name: new_data.name,
+    }
+    .emit();
+    Ok(())
+}
+
+pub fn update_reply(program_id: &Pubkey, accounts: &[AccountInfo], reply: String) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let replier = next_account_info(account_info_iter)?;
+    let reply_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+
+    if !replier.is_signer {
+        msg!("replier needs to sign the txn");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let config = load_config(program_id, config_account)?;
+    validate_text(&reply, &config.reply_limits)?;
+    let mut reply_data = load_account::<StudentIntroReply>(reply_account, program_id)?;
+    let reply_pda = create_pda(
+        program_id,
+        &[
+            reply_data.parent().as_ref(),
+            reply_data.count.to_be_bytes().as_ref(),
+        ],
+        reply_data.bump,
+    )?;
+    if reply_pda != *reply_account.key {
+        msg!("Invalid seeds for reply PDA");
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if reply_data.replier != *replier.key {
+        msg!("only the replier can update the reply");
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+
+    let account_size = StudentIntroReply::get_account_size(
+        reply.clone(),
+        reply_data.parent_reply,
+        reply_data.cohort,
+    );
+    msg!("resizing reply PDA to {} bytes", account_size);
+    resize_account(
+        reply_account,
+        replier,
+        Some(replier),
+        system_program,
+        account_size,
+    )?;
+    reply_data.reply = reply;
+    reply_data.serialize(&mut &mut reply_account.data.borrow_mut()[..])?;
+    msg!("serialized reply PDA");
+    StudentIntroEvent::ReplyUpdated { reply: reply_pda }.emit();
+    Ok(())
+}
+
+pub fn delete_reply(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let replier = next_account_info(account_info_iter)?;
+    let reply_account = next_account_info(account_info_iter)?;
+    let counter_account = next_account_info(account_info_iter)?;
+
+    if !replier.is_signer {
+        msg!("replier needs to sign the txn");
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let reply_data = load_account::<StudentIntroReply>(reply_account, program_id)?;
+    let reply_pda = create_pda(
+        program_id,
+        &[
+            reply_data.parent().as_ref(),
+            reply_data.count.to_be_bytes().as_ref(),
+        ],
+        reply_data.bump,
+    )?;
+    if reply_pda != *reply_account.key {
+        msg!("Invalid seeds for reply PDA");
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if reply_data.replier != *replier.key {
+        msg!("only the replier can delete the reply");
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let counter_pda = create_pda(
+        program_id,
+        &[reply_pda.as_ref(), "reply".as_ref()],
+        reply_data.counter_bump,
+    )?;
+    if counter_pda != *counter_account.key {
+        msg!("invalid seeds for counter PDA");
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+
+    msg!("closing reply PDA: {}", reply_pda);
+    close_account(reply_account, replier)?;
+    // the counter only exists once the reply got replies of its own
+    if !counter_account.data_is_empty() {
+        load_account::<StudentIntroReplyCounter>(counter_account, program_id)?;
+        msg!("closing counter PDA: {}", counter_pda);
+        close_account(counter_account, replier)?;
+    }
+    StudentIntroEvent::ReplyDeleted { reply: reply_pda }.emit();
+    Ok(())
+}
+
+pub fn initialize_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    params: ConfigParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let program_data = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    check_upgrade_authority(program_id, program_data, admin.key)?;
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[b"config"], program_id);
+    if config_pda != *config_account.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    validate_name_limits(&params.name_limits)?;
+
+    let rent_amt = Rent::get()?.minimum_balance(ProgramConfig::SIZE);
+    msg!("creating config account: {:?}", config_account.key);
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            config_account.key,
+            rent_amt,
+            ProgramConfig::SIZE.try_into().unwrap(),
+            program_id,
+        ),
+        &[
+            admin.clone(),
+            config_account.clone(),
+            system_program.clone(),
+        ],
+        &[&[b"config", &[config_bump]]],
+    )?;
+    let config = ProgramConfig {
+        discriminator: ProgramConfig::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        bump: config_bump,
+        mint_bump: find_mint_address(program_id).1,
+        mint_auth_bump: find_mint_auth_address(program_id).1,
+        admin: *admin.key,
+        intro_reward: params.intro_reward,
+        reply_reward: params.reply_reward,
+        max_intro_size: params.max_intro_size,
+        mint_decimals: params.mint_decimals,
+        reward_self_replies: params.reward_self_replies,
+        max_rewarded_replies_per_intro: params.max_rewarded_replies_per_intro,
+        daily_reward_cap: params.daily_reward_cap,
+        one_intro_per_wallet: params.one_intro_per_wallet,
+        name_limits: params.name_limits,
+        msg_limits: params.msg_limits,
+        reply_limits: params.reply_limits,
+        moderators: params.moderators,
+        report_threshold: params.report_threshold,
+    };
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+    msg!("initialized config account");
+    StudentIntroEvent::ConfigUpdated { admin: *admin.key }.emit();
+    Ok(())
+}
+
+pub fn update_config(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Pubkey,
+    params: ConfigParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let mut config = load_config(program_id, config_account)?;
+    if config.admin != *admin.key {
+        msg!("only the config admin can update the config");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    validate_name_limits(&params.name_limits)?;
+    config.admin = new_admin;
+    config.intro_reward = params.intro_reward;
+    config.reply_reward = params.reply_reward;
+    config.max_intro_size = params.max_intro_size;
+    config.mint_decimals = params.mint_decimals;
+    config.reward_self_replies = params.reward_self_replies;
+    config.max_rewarded_replies_per_intro = params.max_rewarded_replies_per_intro;
+    config.daily_reward_cap = params.daily_reward_cap;
+    config.one_intro_per_wallet = params.one_intro_per_wallet;
+    config.name_limits = params.name_limits;
+    config.msg_limits = params.msg_limits;
+    config.reply_limits = params.reply_limits;
+    config.moderators = params.moderators;
+    config.report_threshold = params.report_threshold;
+    if config_account.data_len() != ProgramConfig::SIZE {
+        resize_account(
+            config_account,
+            admin,
+            Some(admin),
+            system_program,
+            ProgramConfig::SIZE,
+        )?;
+    }
+    config.serialize(&mut &mut config_account.data.borrow_mut()[..])?;
+    msg!("updated config account");
+    StudentIntroEvent::ConfigUpdated { admin: new_admin }.emit();
+    Ok(())
+}
+
+pub fn create_mint_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let metadata_account = next_account_info(account_info_iter)?;
+    let mint_account = next_account_info(account_info_iter)?;
+    let mint_auth = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let sysvar_rent = next_account_info(account_info_iter)?;
+    let metadata_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let config = load_config(program_id, config_account)?;
+    if config.admin != *admin.key {
+        msg!("only the config admin can create the mint metadata");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let mint_pda = create_pda(program_id, &[b"token_mint"], config.mint_bump)?;
+    let mint_auth_pda = create_pda(program_id, &[b"token_auth"], config.mint_auth_bump)?;
+    if mint_pda != *mint_account.key || mint_auth_pda != *mint_auth.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let (metadata_pda, _metadata_bump) = mpl_token_metadata::pda::find_metadata_account(&mint_pda);
+    if metadata_pda != *metadata_account.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if RENT_PROGRAM_ID != *sysvar_rent.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if mpl_token_metadata::id() != *metadata_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+
+    msg!("creating metadata account: {:?}", metadata_account.key);
+    invoke_signed(
+        &create_metadata_accounts_v2(
+            *metadata_program.key,
+            *metadata_account.key,
+            *mint_account.key,
+            *mint_auth.key,
+            *admin.key,
+            *mint_auth.key,
+            name.clone(),
+            symbol.clone(),
+            uri.clone(),
+            None,
+            0,
+            true,
+            true,
+            None,
+            None,
+        ),
+        &[
+            metadata_account.clone(),
+            mint_account.clone(),
+            mint_auth.clone(),
+            admin.clone(),
+            system_program.clone(),
+            sysvar_rent.clone(),
+        ],
+        &[&[b"token_auth", &[config.mint_auth_bump]]],
+    )?;
+    msg!("created metadata account successfully");
+    StudentIntroEvent::MintMetadataCreated {
+        metadata: metadata_pda,
+        name,
+        symbol,
+        uri,
+    }
+    .emit();
+    Ok(())
+}
+
+pub fn update_mint_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    symbol: String,
+    uri: String,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let metadata_account = next_account_info(account_info_iter)?;
+    let mint_auth = next_account_info(account_info_iter)?;
+    let metadata_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let config = load_config(program_id, config_account)?;
+    if config.admin != *admin.key {
+        msg!("only the config admin can update the mint metadata");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let mint_pda = create_pda(program_id, &[b"token_mint"], config.mint_bump)?;
+    let mint_auth_pda = create_pda(program_id, &[b"token_auth"], config.mint_auth_bump)?;
+    if mint_auth_pda != *mint_auth.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let (metadata_pda, _metadata_bump) = mpl_token_metadata::pda::find_metadata_account(&mint_pda);
+    if metadata_pda != *metadata_account.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if mpl_token_metadata::id() != *metadata_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+
+    msg!("updating metadata account: {:?}", metadata_account.key);
+    invoke_signed(
+        &update_metadata_accounts_v2(
+            *metadata_program.key,
+            *metadata_account.key,
+            *mint_auth.key,
+            None,
+            Some(DataV2 {
+                name: name.clone(),
+                symbol: symbol.clone(),
+                uri: uri.clone(),
+                seller_fee_basis_points: 0,
+                creators: None,
+                collection: None,
+                uses: None,
+            }),
+            None,
+            None,
+        ),
+        &[metadata_account.clone(), mint_auth.clone()],
+        &[&[b"token_auth", &[config.mint_auth_bump]]],
+    )?;
+    msg!("updated metadata account successfully");
+    StudentIntroEvent::MintMetadataUpdated {
+        metadata: metadata_pda,
+        name,
+        symbol,
+        uri,
+    }
+    .emit();
+    Ok(())
+}
+
+pub fn migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let payer = next_account_info(account_info_iter)?;
+    let account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !payer.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = account.data.borrow();
+    let migrated = match data.get(..8) {
+        Some(d) if d == StudentIntroAccountState::DISCRIMINATOR => {
+            StudentIntroAccountState::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == StudentIntroReplyCounter::DISCRIMINATOR => {
+            StudentIntroReplyCounter::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == StudentIntroReply::DISCRIMINATOR => {
+            StudentIntroReply::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == ProgramConfig::DISCRIMINATOR => {
+            ProgramConfig::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == StudentIntroReaction::DISCRIMINATOR => {
+            StudentIntroReaction::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == ReplierRecord::DISCRIMINATOR => {
+            ReplierRecord::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == RewardTracker::DISCRIMINATOR => {
+            RewardTracker::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == UserProfile::DISCRIMINATOR => {
+            UserProfile::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == IntroMarker::DISCRIMINATOR => {
+            IntroMarker::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == ContentReport::DISCRIMINATOR => {
+            ContentReport::unpack(&data, program_id)?.try_to_vec()?
+        }
+        Some(d) if d == Cohort::DISCRIMINATOR => Cohort::unpack(&data, program_id)?.try_to_vec()?,
+        _ => migrate_v0(program_id, &data)?,
+    };
+    if migrated[..] == data[..] {
+        msg!("account already migrated");
+        return Ok(());
+    }
+    drop(data);
+
+    msg!("migrating account: {:?}", account.key);
+    let rent_amt = Rent::get()?.minimum_balance(migrated.len());
+    let lamports = account.lamports();
+    if rent_amt > lamports {
+        // growing accounts are topped up by the payer, shrinking ones keep
+        // their lamports until they are closed
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, rent_amt - lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    }
+    account.realloc(migrated.len(), false)?;
+    account.data.borrow_mut().copy_from_slice(&migrated);
+    msg!("migrated account to {} bytes", migrated.len());
+    StudentIntroEvent::AccountMigrated {
+        account: *account.key,
+        len: migrated.len() as u64,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Converts an account written with a String discriminator to the latest layout.
+fn migrate_v0(program_id: &Pubkey, data: &[u8]) -> Result<Vec<u8>, ProgramError> {
+    let discriminator =
+        String::deserialize(&mut &data[..]).map_err(|_| StudentIntroError::CorruptAccountData)?;
+    msg!(
+        "found {} account with a String discriminator",
+        discriminator
+    );
+    let migrated = match discriminator.as_str() {
+        "intro" => {
+            let mut intro = StudentIntroAccountState::from(
+                try_from_slice_unchecked::<v0::StudentIntroAccountState>(data)
+                    .map_err(|_| StudentIntroError::CorruptAccountData)?,
+            );
+            intro.find_bumps(program_id);
+            intro.try_to_vec()?
+        }
+        "counter" => StudentIntroReplyCounter::from(
+            try_from_slice_unchecked::<v0::StudentIntroReplyCounter>(data)
+                .map_err(|_| StudentIntroError::CorruptAccountData)?,
+        )
+        .try_to_vec()?,
+        "reply" => {
+            let mut reply = StudentIntroReply::from(
+                try_from_slice_unchecked::<v0::StudentIntroReply>(data)
+                    .map_err(|_| StudentIntroError::CorruptAccountData)?,
+            );
+            reply.find_bumps(program_id);
+            reply.try_to_vec()?
+        }
+        "config" => {
+            let mut config = ProgramConfig::from(
+                try_from_slice_unchecked::<v0::ProgramConfig>(data)
+                    .map_err(|_| StudentIntroError::CorruptAccountData)?,
+            );
+            config.find_bumps(program_id);
+            config.try_to_vec()?
+        }
+        _ => return Err(StudentIntroError::InvalidDiscriminator.into()),
+    };
+    Ok(migrated)
+}
+
+pub fn react(program_id: &Pubkey, accounts: &[AccountInfo], kind: ReactionKind) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let target_account = next_account_info(account_info_iter)?;
+    let reaction_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let (key, target_slot) = update_reaction_counts(
+        program_id,
+        target_account,
+        user,
+        system_program,
+        |counts, _| {
+            counts[kind as usize] += 1;
+        },
+    )?;
+    let (reaction_pda, reaction_bump) =
+        Pubkey::find_program_address(&[b"reaction", key.as_ref(), user.key.as_ref()], program_id);
+    if reaction_pda != *reaction_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if reaction_account.data_is_empty() {
+        create_pda_account(
+            program_id,
+            user,
+            reaction_account,
+            system_program,
+            StudentIntroReaction::SIZE,
+            &[
+                b"reaction",
+                key.as_ref(),
+                user.key.as_ref(),
+                &[reaction_bump],
+            ],
+        )?;
+    } else {
+        let previous = load_account::<StudentIntroReaction>(reaction_account, program_id)?;
+        if previous.target_slot == target_slot {
+            msg!("user already reacted to {}", target_account.key);
+            return Err(StudentIntroError::AlreadyReacted.into());
+        }
+        // the reaction was left on an intro closed since, it is taken over
+        msg!("replacing reaction to a closed intro: {}", reaction_pda);
+        if reaction_account.data_len() != StudentIntroReaction::SIZE {
+            resize_account(
+                reaction_account,
+                user,
+                None,
+                system_program,
+                StudentIntroReaction::SIZE,
+            )?;
+        }
+    }
+    let reaction = StudentIntroReaction {
+        discriminator: StudentIntroReaction::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        bump: reaction_bump,
+        target: key,
+        user: *user.key,
+        kind,
+        target_slot,
+    };
+    reaction.serialize(&mut &mut reaction_account.data.borrow_mut()[..])?;
+    msg!("created reaction PDA: {}", reaction_pda);
+    StudentIntroEvent::ReactionAdded {
+        target: reaction.target,
+        user: reaction.user,
+        kind,
+    }
+    .emit();
+    Ok(())
+}
+
+pub fn unreact(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let target_account = next_account_info(account_info_iter)?;
+    let reaction_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !user.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let reaction = load_account::<StudentIntroReaction>(reaction_account, program_id)?;
+    let reaction_pda = create_pda(
+        program_id,
+        &[
+            b"reaction",
+            reaction.target.as_ref(),
+            reaction.user.as_ref(),
+        ],
+        reaction.bump,
+    )?;
+    if reaction_pda != *reaction_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if reaction.user != *user.key {
+        msg!("only the user who reacted can remove the reaction");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    // the target may have been closed since, its counts are gone with it
+    if target_account.owner == program_id && !target_account.data_is_empty() {
+        let (key, _) = update_reaction_counts(
+            program_id,
+            target_account,
+            user,
+            system_program,
+            |counts, slot| {
+                // a reaction left on an intro closed since does not count for
+                // the intro now posted at its address
+                if slot == reaction.target_slot {
+                    counts[reaction.kind as usize] =
+                        counts[reaction.kind as usize].saturating_sub(1);
+                }
+            },
+        )?;
+        // reactions to renamed intros used to be keyed by the renamed address
+        if reaction.target != key && reaction.target != *target_account.key {
+            return Err(StudentIntroError::IncorrectAccountPassed.into());
+        }
+    } else if reaction.target != *target_account.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+
+    msg!("closing reaction PDA: {}", reaction_pda);
+    close_account(reaction_account, user)?;
+    StudentIntroEvent::ReactionRemoved {
+        target: reaction.target,
+        user: reaction.user,
+        kind: reaction.kind,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Closes the reply counter of a closing intro to `destination` unless the
+/// intro got replies. Those stay seeded under the counter, so it is kept and
+/// marked uninitialized for an intro posted at the same address to take over.
+/// An older counter layout is upgraded out of the closing intro's rent.
+fn close_intro_counter(
+    intro_account: &AccountInfo,
+    counter_account: &AccountInfo,
+    counter: StudentIntroReplyCounter,
+    destination: &AccountInfo,
+) -> ProgramResult {
+    if counter.counter == 0 {
+        msg!("closing counter PDA: {}", counter_account.key);
+        return close_account(counter_account, destination);
+    }
+    if counter_account.data_len() != StudentIntroReplyCounter::SIZE {
+        let rent_amt = Rent::get()?.minimum_balance(StudentIntroReplyCounter::SIZE);
+        let top_up = rent_amt.saturating_sub(counter_account.lamports());
+        **intro_account.try_borrow_mut_lamports()? = intro_account
+            .lamports()
+            .checked_sub(top_up)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        **counter_account.try_borrow_mut_lamports()? += top_up;
+        counter_account.realloc(StudentIntroReplyCounter::SIZE, false)?;
+    }
+    let detached = StudentIntroReplyCounter {
+        is_initialized: false,
+        ..counter
+    };
+    detached.serialize(&mut &mut counter_account.data.borrow_mut()[..])?;
+    msg!("detached counter PDA: {}", counter_account.key);
+    Ok(())
+}
+
+/// Loads a reply counter left behind by a closed intro, failing if the intro
+/// it belongs to is still live, possibly under another name.
+fn load_detached_counter(
+    program_id: &Pubkey,
+    counter_account: &AccountInfo,
+) -> Result<StudentIntroReplyCounter, ProgramError> {
+    if counter_account.owner != program_id {
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = counter_account.try_borrow_data()?;
+    if !data.starts_with(&StudentIntroReplyCounter::DISCRIMINATOR) {
+        return Err(StudentIntroError::InvalidDiscriminator.into());
+    }
+    let counter = StudentIntroReplyCounter::unpack(&data, program_id)?;
+    if counter.is_initialized {
+        msg!("counter {} belongs to a live intro", counter_account.key);
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(counter)
+}
+
+/// Loads the reward tracker of `user`, creating it on the user's first reward.
+fn load_reward_tracker<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    tracker_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<RewardTracker, ProgramError> {
+    if tracker_account.data_is_empty() {
+        let (tracker_pda, bump) =
+            Pubkey::find_program_address(&[b"rewards", user.key.as_ref()], program_id);
+        if tracker_pda != *tracker_account.key {
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        create_pda_account(
+            program_id,
+            user,
+            tracker_account,
+            system_program,
+            RewardTracker::SIZE,
+            &[b"rewards", user.key.as_ref(), &[bump]],
+        )?;
+        return Ok(RewardTracker {
+            discriminator: RewardTracker::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump,
+            day: 0,
+            rewarded_today: 0,
+        });
+    }
+    let tracker = load_account::<RewardTracker>(tracker_account, program_id)?;
+    if create_pda(program_id, &[b"rewards", user.key.as_ref()], tracker.bump)?
+        != *tracker_account.key
+    {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    Ok(tracker)
+}
+
+/// Records the first intro of `wallet` in its marker, failing if it already
+/// has one and `one_per_wallet` is set. Each cohort has its own markers.
+fn mark_intro<'a>(
+    program_id: &Pubkey,
+    wallet: &AccountInfo<'a>,
+    marker_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    intro: &Pubkey,
+    cohort: Option<&Pubkey>,
+    one_per_wallet: bool,
+) -> ProgramResult {
+    let mut seeds = intro_marker_seeds(cohort, wallet.key);
+    if !marker_account.data_is_empty() {
+        let marker = load_account::<IntroMarker>(marker_account, program_id)?;
+        if create_pda(program_id, &seeds, marker.bump)? != *marker_account.key {
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        if one_per_wallet {
+            msg!("wallet already posted intro {}", marker.intro);
+            return Err(StudentIntroError::IntroLimitReached.into());
+        }
+        return Ok(());
+    }
+    let (marker_pda, bump) = Pubkey::find_program_address(&seeds, program_id);
+    if marker_pda != *marker_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    let bump_seed = [bump];
+    seeds.push(&bump_seed);
+    create_pda_account(
+        program_id,
+        wallet,
+        marker_account,
+        system_program,
+        IntroMarker::SIZE,
+        &seeds,
+    )?;
+    let marker = IntroMarker {
+        discriminator: IntroMarker::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        bump,
+        intro: *intro,
+    };
+    marker.serialize(&mut &mut marker_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Points the intro marker of `wallet` at `to` when it marks `from`. Wallets
+/// that posted before markers existed have none.
+fn move_intro_marker(
+    program_id: &Pubkey,
+    wallet: &Pubkey,
+    marker_account: &AccountInfo,
+    cohort: Option<&Pubkey>,
+    from: &Pubkey,
+    to: &Pubkey,
+) -> ProgramResult {
+    let seeds = intro_marker_seeds(cohort, wallet);
+    if marker_account.data_is_empty() {
+        if Pubkey::find_program_address(&seeds, program_id).0 != *marker_account.key {
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        return Ok(());
+    }
+    let mut marker = load_account::<IntroMarker>(marker_account, program_id)?;
+    if create_pda(program_id, &seeds, marker.bump)? != *marker_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if marker.intro == *from {
+        marker.intro = *to;
+        marker.serialize(&mut &mut marker_account.data.borrow_mut()[..])?;
+        msg!("moved intro marker to {}", to);
+    }
+    Ok(())
+}
+
+/// Loads the profile of `user`, a fresh one joined at `now` if it does not
+/// exist yet. `store_user_profile` creates the account.
+fn load_user_profile(
+    program_id: &Pubkey,
+    user: &AccountInfo,
+    profile_account: &AccountInfo,
+    now: i64,
+) -> Result<UserProfile, ProgramError> {
+    if profile_account.data_is_empty() {
+        let (profile_pda, bump) =
+            Pubkey::find_program_address(&[b"profile", user.key.as_ref()], program_id);
+        if profile_pda != *profile_account.key {
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        return Ok(UserProfile {
+            discriminator: UserProfile::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump,
+            wallet: *user.key,
+            display_name: String::new(),
+            total_intros: 0,
+            total_replies: 0,
+            total_tokens_earned: 0,
+            joined_at: now,
+        });
+    }
+    let profile = load_account::<UserProfile>(profile_account, program_id)?;
+    if create_pda(program_id, &[b"profile", user.key.as_ref()], profile.bump)?
+        != *profile_account.key
+    {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    Ok(profile)
+}
+
+/// Writes `profile` back, creating or resizing its account at the expense of
+/// `user`.
+fn store_user_profile<'a>(
+    program_id: &Pubkey,
+    user: &AccountInfo<'a>,
+    profile_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    profile: &UserProfile,
+) -> ProgramResult {
+    let size = UserProfile::get_account_size(&profile.display_name);
+    if profile_account.data_is_empty() {
+        create_pda_account(
+            program_id,
+            user,
+            profile_account,
+            system_program,
+            size,
+            &[b"profile", user.key.as_ref(), &[profile.bump]],
+        )?;
+        msg!("created user profile");
+    } else if profile_account.data_len() != size {
+        resize_account(profile_account, user, Some(user), system_program, size)?;
+    }
+    profile.serialize(&mut &mut profile_account.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Loads the record of `replier` under `intro`, creating it on the replier's
+/// first reply there.
+fn load_replier_record<'a>(
+    program_id: &Pubkey,
+    intro: &Pubkey,
+    replier: &AccountInfo<'a>,
+    record_account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+) -> Result<ReplierRecord, ProgramError> {
+    let seeds: &[&[u8]] = &[b"replier", intro.as_ref(), replier.key.as_ref()];
+    if record_account.data_is_empty() {
+        let (record_pda, bump) = Pubkey::find_program_address(seeds, program_id);
+        if record_pda != *record_account.key {
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        create_pda_account(
+            program_id,
+            replier,
+            record_account,
+            system_program,
+            ReplierRecord::SIZE,
+            &[b"replier", intro.as_ref(), replier.key.as_ref(), &[bump]],
+        )?;
+        return Ok(ReplierRecord {
+            discriminator: ReplierRecord::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump,
+            rewarded_replies: 0,
+        });
+    }
+    let record = load_account::<ReplierRecord>(record_account, program_id)?;
+    if create_pda(program_id, seeds, record.bump)? != *record_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    Ok(record)
+}
+
+/// Creates a rent exempt, program owned account of `size` bytes at the PDA
+/// signed for by `signer_seeds`, paid by `payer`.
+fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    account: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    size: usize,
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    let rent_amt = Rent::get()?.minimum_balance(size);
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            account.key,
+            rent_amt,
+            size.try_into().unwrap(),
+            program_id,
+        ),
+        &[payer.clone(), account.clone(), system_program.clone()],
+        &[signer_seeds],
+    )
+}
+
+/// Sets the hidden flag of an intro or reply, signed by the admin or a moderator.
+/// Unhiding also clears the report count, so the target needs a fresh round of
+/// reports to be hidden again.
+pub fn set_hidden(program_id: &Pubkey, accounts: &[AccountInfo], hidden: bool) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let moderator = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let target = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !moderator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let config = load_config(program_id, config_account)?;
+    if !config.is_moderator(moderator.key) {
+        msg!("only the admin or a moderator can moderate content");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let is_intro = target
+        .try_borrow_data()?
+        .starts_with(&StudentIntroAccountState::DISCRIMINATOR);
+    let data = if is_intro {
+        let mut intro = load_account::<StudentIntroAccountState>(target, program_id)?;
+        intro.hidden = hidden;
+        if !hidden {
+            intro.report_count = 0;
+        }
+        intro.try_to_vec()?
+    } else {
+        let mut reply = load_account::<StudentIntroReply>(target, program_id)?;
+        reply.hidden = hidden;
+        if !hidden {
+            reply.report_count = 0;
+        }
+        reply.try_to_vec()?
+    };
+    if target.data_len() != data.len() {
+        resize_account(target, moderator, None, system_program, data.len())?;
+    }
+    target.try_borrow_mut_data()?.copy_from_slice(&data);
+    msg!("set hidden to {} for {}", hidden, target.key);
+    if hidden {
+        StudentIntroEvent::ContentHidden {
+            target: *target.key,
+            moderator: *moderator.key,
+        }
+        .emit();
+    } else {
+        StudentIntroEvent::ContentUnhidden {
+            target: *target.key,
+            moderator: *moderator.key,
+        }
+        .emit();
+    }
+    Ok(())
+}
+
+/// Closes an intro with its reply counter, or a reply, refunding the rent to
+/// its author. Signed by the admin or a moderator.
+pub fn force_close(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let moderator = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let target = next_account_info(account_info_iter)?;
+    let author = next_account_info(account_info_iter)?;
+    let counter_account = next_account_info(account_info_iter)?;
+
+    if !moderator.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    let config = load_config(program_id, config_account)?;
+    if !config.is_moderator(moderator.key) {
+        msg!("only the admin or a moderator can moderate content");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let is_intro = target
+        .try_borrow_data()?
+        .starts_with(&StudentIntroAccountState::DISCRIMINATOR);
+    if is_intro {
+        let intro = load_account::<StudentIntroAccountState>(target, program_id)?;
+        if intro.identity != *author.key {
+            msg!("author is not the intro's identity");
+            return Err(StudentIntroError::IncorrectAccountPassed.into());
+        }
+        let counter_data = load_account::<StudentIntroReplyCounter>(counter_account, program_id)?;
+        let counter_pda = create_pda(
+            program_id,
+            &[intro.thread(target.key).as_ref(), "reply".as_ref()],
+            intro.counter_bump,
+        )?;
+        if counter_pda != *counter_account.key {
+            msg!("invalid seeds for counter PDA");
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        close_intro_counter(target, counter_account, counter_data, author)?;
+    } else {
+        let reply = load_account::<StudentIntroReply>(target, program_id)?;
+        if reply.replier != *author.key {
+            msg!("author is not the replier");
+            return Err(StudentIntroError::IncorrectAccountPassed.into());
+        }
+        let counter_pda = create_pda(
+            program_id,
+            &[target.key.as_ref(), "reply".as_ref()],
+            reply.counter_bump,
+        )?;
+        if counter_pda != *counter_account.key {
+            msg!("invalid seeds for counter PDA");
+            return Err(StudentIntroError::InvalidPda.into());
+        }
+        // the counter only exists once the reply got replies of its own
+        if !counter_account.data_is_empty() {
+            load_account::<StudentIntroReplyCounter>(counter_account, program_id)?;
+            msg!("closing counter PDA: {}", counter_pda);
+            close_account(counter_account, author)?;
+        }
+    }
+    msg!("force closing {}", target.key);
+    close_account(target, author)?;
+    StudentIntroEvent::ContentForceClosed {
+        target: *target.key,
+        moderator: *moderator.key,
+        author: *author.key,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Reports an intro or reply, hiding it once its reports reach the config's
+/// report threshold.
+pub fn report_content(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    reason: ReportReason,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let reporter = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let target = next_account_info(account_info_iter)?;
+    let report_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !reporter.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let config = load_config(program_id, config_account)?;
+
+    let reaches_threshold =
+        |count: u32| config.report_threshold != 0 && count >= config.report_threshold;
+    let is_intro = target
+        .try_borrow_data()?
+        .starts_with(&StudentIntroAccountState::DISCRIMINATOR);
+    // reports on intros are keyed by their thread, as their report count
+    // carries over renames
+    let (key, data, report_count, auto_hidden) = if is_intro {
+        let mut intro = load_account::<StudentIntroAccountState>(target, program_id)?;
+        intro.report_count = intro.report_count.saturating_add(1);
+        let auto_hidden = !intro.hidden && reaches_threshold(intro.report_count);
+        intro.hidden |= auto_hidden;
+        (
+            *intro.thread(target.key),
+            intro.try_to_vec()?,
+            intro.report_count,
+            auto_hidden,
+        )
+    } else {
+        let mut reply = load_account::<StudentIntroReply>(target, program_id)?;
+        reply.report_count = reply.report_count.saturating_add(1);
+        let auto_hidden = !reply.hidden && reaches_threshold(reply.report_count);
+        reply.hidden |= auto_hidden;
+        (
+            *target.key,
+            reply.try_to_vec()?,
+            reply.report_count,
+            auto_hidden,
+        )
+    };
+    let (report_pda, report_bump) = Pubkey::find_program_address(
+        &[b"report", key.as_ref(), reporter.key.as_ref()],
+        program_id,
+    );
+    if report_pda != *report_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    if !report_account.data_is_empty() {
+        msg!("user already reported {}", target.key);
+        return Err(StudentIntroError::AlreadyReported.into());
+    }
+    if target.data_len() != data.len() {
+        resize_account(target, reporter, None, system_program, data.len())?;
+    }
+    target.try_borrow_mut_data()?.copy_from_slice(&data);
+
+    create_pda_account(
+        program_id,
+        reporter,
+        report_account,
+        system_program,
+        ContentReport::SIZE,
+        &[
+            b"report",
+            key.as_ref(),
+            reporter.key.as_ref(),
+            &[report_bump],
+        ],
+    )?;
+    let report = ContentReport {
+        discriminator: ContentReport::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        bump: report_bump,
+        target: key,
+        reporter: *reporter.key,
+        reason,
+    };
+    report.serialize(&mut &mut report_account.data.borrow_mut()[..])?;
+    msg!("created report PDA: {}", report_pda);
+    StudentIntroEvent::ContentReported {
+        target: *target.key,
+        reporter: report.reporter,
+        reason,
+        report_count,
+    }
+    .emit();
+    if auto_hidden {
+        msg!("hiding {} after {} reports", target.key, report_count);
+        StudentIntroEvent::ContentAutoHidden {
+            target: *target.key,
+            report_count,
+        }
+        .emit();
+    }
+    Ok(())
+}
+
+/// Creates a cohort, signed by the config admin who pays for it.
+pub fn create_cohort(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    name: String,
+    admin: Pubkey,
+    params: CohortParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let config_admin = next_account_info(account_info_iter)?;
+    let config_account = next_account_info(account_info_iter)?;
+    let cohort_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !config_admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let config = load_config(program_id, config_account)?;
+    if config.admin != *config_admin.key {
+        msg!("only the config admin can create cohorts");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    validate_name(&name, &config.name_limits)?;
+    let (cohort_pda, bump) =
+        Pubkey::find_program_address(&[b"cohort", name.as_bytes()], program_id);
+    if cohort_pda != *cohort_account.key {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    create_pda_account(
+        program_id,
+        config_admin,
+        cohort_account,
+        system_program,
+        Cohort::get_account_size(&name, &params),
+        &[b"cohort", name.as_bytes(), &[bump]],
+    )?;
+    let cohort = Cohort {
+        discriminator: Cohort::DISCRIMINATOR,
+        version: ACCOUNT_VERSION,
+        is_initialized: true,
+        bump,
+        admin,
+        name,
+        open: params.open,
+        intro_reward: params.intro_reward,
+        reply_reward: params.reply_reward,
+    };
+    cohort.serialize(&mut &mut cohort_account.data.borrow_mut()[..])?;
+    msg!("created cohort PDA: {}", cohort_pda);
+    StudentIntroEvent::CohortCreated {
+        cohort: cohort_pda,
+        admin,
+        name: cohort.name,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Updates a cohort, signed by its admin.
+pub fn update_cohort(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_admin: Pubkey,
+    params: CohortParams,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let cohort_account = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
+    if !admin.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if SYSTEM_PROGRAM_ID != *system_program.key {
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    let mut cohort = load_cohort(program_id, cohort_account)?;
+    if cohort.admin != *admin.key {
+        msg!("only the cohort admin can update the cohort");
+        return Err(StudentIntroError::Unauthorized.into());
+    }
+    let size = Cohort::get_account_size(&cohort.name, &params);
+    cohort.admin = new_admin;
+    cohort.open = params.open;
+    cohort.intro_reward = params.intro_reward;
+    cohort.reply_reward = params.reply_reward;
+    if cohort_account.data_len() != size {
+        // the admin need not have paid the rent, a shrinking cohort keeps its excess
+        resize_account(cohort_account, admin, None, system_program, size)?;
+    }
+    cohort.serialize(&mut &mut cohort_account.data.borrow_mut()[..])?;
+    msg!("updated cohort {}", cohort.name);
+    StudentIntroEvent::CohortUpdated {
+        cohort: *cohort_account.key,
+        admin: new_admin,
+    }
+    .emit();
+    Ok(())
+}
+
+/// Loads a cohort account and checks that it is the PDA of the cohort's name.
+fn load_cohort(program_id: &Pubkey, cohort_account: &AccountInfo) -> Result<Cohort, ProgramError> {
+    let cohort = load_account::<Cohort>(cohort_account, program_id)?;
+    if create_pda(
+        program_id,
+        &[b"cohort", cohort.name.as_bytes()],
+        cohort.bump,
+    )? != *cohort_account.key
+    {
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    Ok(cohort)
+}
+
+/// Loads a cohort that content is posted in, failing if it is closed.
+fn load_open_cohort(
+    program_id: &Pubkey,
+    cohort_account: &AccountInfo,
+) -> Result<Cohort, ProgramError> {
+    let cohort = load_cohort(program_id, cohort_account)?;
+    if !cohort.open {
+        msg!("cohort {} is closed", cohort.name);
+        return Err(StudentIntroError::CohortClosed.into());
+    }
+    Ok(cohort)
+}
+
+/// Applies `update` to the reaction counts of an intro or reply, given along
+/// with the intro's `created_slot` or 0 for a reply, and writes it back in the
+/// current layout, `payer` covers the rent if it grows. The excess of a
+/// shrinking account is not `payer`'s and stays in the account. Returns the
+/// address reactions to the target are keyed by, the thread of an intro so they
+/// follow it across renames, and the slot passed to `update`.
+fn update_reaction_counts<'a>(
+    program_id: &Pubkey,
+    target: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    update: impl FnOnce(&mut [u64; ReactionKind::COUNT], u64),
+) -> Result<(Pubkey, u64), ProgramError> {
+    let is_intro = target
+        .try_borrow_data()?
+        .starts_with(&StudentIntroAccountState::DISCRIMINATOR);
+    let (key, slot, data) = if is_intro {
+        let mut intro = load_account::<StudentIntroAccountState>(target, program_id)?;
+        update(&mut intro.reaction_counts, intro.created_slot);
+        (
+            *intro.thread(target.key),
+            intro.created_slot,
+            intro.try_to_vec()?,
+        )
+    } else {
+        let mut reply = load_account::<StudentIntroReply>(target, program_id)?;
+        update(&mut reply.reaction_counts, 0);
+        (*target.key, 0, reply.try_to_vec()?)
+    };
+    if target.data_len() != data.len() {
+        resize_account(target, payer, None, system_program, data.len())?;
+    }
+    target.try_borrow_mut_data()?.copy_from_slice(&data);
+    Ok((key, slot))
+}
+
+/// Checks that `authority` is the upgrade authority recorded in the program
+/// data account of this program.
+fn check_upgrade_authority(
+    program_id: &Pubkey,
+    program_data: &AccountInfo,
+    authority: &Pubkey,
+) -> ProgramResult {
+    let (program_data_address, _) =
+        Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::id());
+    if program_data_address != *program_data.key {
+        msg!("Invalid program data account passed");
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    if *program_data.owner != bpf_loader_upgradeable::id() {
+        return Err(ProgramError::IllegalOwner);
+    }
+    match program_data.deserialize_data::<UpgradeableLoaderState>() {
+        Ok(UpgradeableLoaderState::ProgramData {
+            upgrade_authority_address: Some(upgrade_authority),
+            ..
+        }) if upgrade_authority == *authority => Ok(()),
+        _ => {
+            msg!("signer is not the program's upgrade authority");
+            Err(StudentIntroError::Unauthorized.into())
+        }
+    }
+}
+
+/// Loads the config account and checks that it is the config PDA.
+fn load_config(
+    program_id: &Pubkey,
+    config_account: &AccountInfo,
+) -> Result<ProgramConfig, ProgramError> {
+    let config = load_account::<ProgramConfig>(config_account, program_id)?;
+    if create_pda(program_id, &[b"config"], config.bump)? != *config_account.key {
+        msg!("Invalid config account passed");
+        return Err(StudentIntroError::IncorrectAccountPassed.into());
+    }
+    Ok(config)
+}
+
+/// Recreates a PDA from its seeds and stored bump, which costs far fewer
+/// compute units than searching the bump with `find_program_address`.
+fn create_pda(program_id: &Pubkey, seeds: &[&[u8]], bump: u8) -> Result<Pubkey, ProgramError> {
+    let bump = [bump];
+    let mut seeds = seeds.to_vec();
+    seeds.push(&bump);
+    Pubkey::create_program_address(&seeds, program_id)
+        .map_err(|_| StudentIntroError::InvalidPda.into())
+}
+
+/// `create_pda` for a bump supplied by a client, which must be the canonical
+/// bump `find_program_address` returns, the highest one giving a valid address.
+fn create_canonical_pda(
+    program_id: &Pubkey,
+    seeds: &[&[u8]],
+    bump: u8,
+) -> Result<Pubkey, ProgramError> {
+    let pda = create_pda(program_id, seeds, bump)?;
+    if (bump..=u8::MAX)
+        .skip(1)
+        .any(|higher| create_pda(program_id, seeds, higher).is_ok())
+    {
+        msg!("bump {} is not canonical", bump);
+        return Err(StudentIntroError::InvalidPda.into());
+    }
+    Ok(pda)
+}
+
+/// Reallocates a program owned account to `new_len` bytes, `payer` tops up the
+/// rent when the account grows. The excess of a shrinking account is refunded
+/// to `refund_to`, which must be the account's owner, and otherwise stays in
+/// the account until it is closed.
+fn resize_account<'a>(
+    account: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    refund_to: Option<&AccountInfo<'a>>,
+    system_program: &AccountInfo<'a>,
+    new_len: usize,
+) -> ProgramResult {
+    let rent_amt = Rent::get()?.minimum_balance(new_len);
+    let lamports = account.lamports();
+    if rent_amt > lamports {
+        invoke(
+            &system_instruction::transfer(payer.key, account.key, rent_amt - lamports),
+            &[payer.clone(), account.clone(), system_program.clone()],
+        )?;
+    } else if let Some(refund_to) = refund_to.filter(|_| lamports > rent_amt) {
+        let excess = lamports - rent_amt;
+        **account.try_borrow_mut_lamports()? -= excess;
+        **refund_to.try_borrow_mut_lamports()? += excess;
+    }
+    account.realloc(new_len, false)
+}
+
+/// Zeroes the data of a program owned account and moves all of its lamports
+/// to `destination`, the runtime then purges the account.
+fn close_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = account.lamports();
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::InvalidArgument)?;
+    **account.try_borrow_mut_lamports()? = 0;
+    account.try_borrow_mut_data()?.fill(0);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction;
+    use borsh::BorshDeserialize;
+    use solana_program::{instruction::Instruction, program_pack::Pack};
+    use spl_associated_token_account::instruction::create_associated_token_account;
+    use {
+        assert_matches::*,
+        solana_program_test::*,
+        solana_sdk::{
+            account::{Account, AccountSharedData},
+            hash::Hash,
+            signature::{Keypair, Signer},
+            transaction::Transaction,
+        },
+    };
+
+    fn program_test(program_id: Pubkey) -> ProgramTest {
+        ProgramTest::new(
+            "solana_student_intro_reply",
+            program_id,
+            processor!(process_instruction),
+        )
+    }
+
+    /// Starts the program with the payer recorded as its upgrade authority.
+    async fn start(program_id: Pubkey) -> (BanksClient, Keypair, Hash) {
+        let context = start_with_context(program_id).await;
+        (context.banks_client, context.payer, context.last_blockhash)
+    }
+
+    /// Like `start`, keeping the context to overwrite accounts later.
+    async fn start_with_context(program_id: Pubkey) -> ProgramTestContext {
+        let mut context = program_test(program_id).start_with_context().await;
+        let (program_data, _) = instruction::find_program_data_address(&program_id);
+        let program_data_acc = Account::new_data(
+            Rent::default()
+                .minimum_balance(UpgradeableLoaderState::programdata_data_offset().unwrap()),
+            &UpgradeableLoaderState::ProgramData {
+                slot: 0,
+                upgrade_authority_address: Some(context.payer.pubkey()),
+            },
+            &bpf_loader_upgradeable::id(),
+        )
+        .unwrap();
+        context.set_account(&program_data, &AccountSharedData::from(program_data_acc));
+        context
+    }
+
+    /// Compute units consumed by `ix`, read from a preflight simulation that a
+    /// trailing transfer out of an empty account makes fail, so nothing is
+    /// committed.
+    #[cfg(feature = "test-bpf")]
+    async fn compute_units(
+        banks_client: &mut BanksClient,
+        payer: &Keypair,
+        ix: Instruction,
+        recent_blockhash: Hash,
+    ) -> u64 {
+        use solana_sdk::transaction::TransactionError;
+
+        let empty = Keypair::new();
+        let fail_ix = system_instruction::transfer(&empty.pubkey(), &payer.pubkey(), 1);
+        let mut tx = Transaction::new_with_payer(&[ix, fail_ix], Some(&payer.pubkey()));
+        tx.sign(&[payer, &empty], recent_blockhash);
+        match banks_client.process_transaction_with_preflight(tx).await {
+            Err(BanksClientError::SimulationError {
+                err: TransactionError::InstructionError(1, _),
+                units_consumed,
+                ..
+            }) => units_consumed,
+            result => panic!("unexpected simulation result: {:?}", result),
+        }
+    }
+
+    /// Config, mint and the payer's token account, needed before any intro.
+    fn setup_ixs(program_id: &Pubkey, payer: &Pubkey) -> Vec<Instruction> {
+        let (mint, _) = instruction::find_mint_address(program_id);
+        vec![
+            instruction::initialize_config(program_id, payer, ConfigParams::default()),
+            instruction::initialize_mint(program_id, payer),
+            create_associated_token_account(payer, payer, &mint),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_init_mint_acc_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+
+        let init_config_ix =
+            instruction::initialize_config(&program_id, &payer.pubkey(), ConfigParams::default());
+        let ix = instruction::initialize_mint(&program_id, &payer.pubkey());
+        let mut tx = Transaction::new_with_payer(&[init_config_ix, ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+    }
+
+    #[tokio::test]
+    async fn test_add_student_intro_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Developing solana jutsu".to_owned();
+        let ixs = setup_ixs(&program_id, &payer.pubkey());
+        let add_intro_ix = instruction::add_student_intro(&program_id, &payer.pubkey(), name, msg);
+        let mut tx =
+            Transaction::new_with_payer(&[ixs, vec![add_intro_ix]].concat(), Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+    }
+
+    #[tokio::test]
+    async fn test_update_student_intro_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let (prev_msg, new_msg) = (
+            "Looking to develop solana jutsu".to_owned(),
+            "Exploring solana ecosystem".to_owned(),
+        );
+        let ixs = setup_ixs(&program_id, &payer.pubkey());
+        let add_intro_ix =
+            instruction::add_student_intro(&program_id, &payer.pubkey(), name.clone(), prev_msg);
+        let update_intro_ix = instruction::update_student_intro(
+            &program_id,
+            &payer.pubkey(),
+            name.clone(),
+            new_msg.clone(),
+        );
+        let mut tx = Transaction::new_with_payer(
+            &[ixs, vec![add_intro_ix, update_intro_ix]].concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        assert_eq!(
+            intro_acc.data.len(),
+            StudentIntroAccountState::get_account_size(name, new_msg.clone(), None, None)
+        );
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.msg, new_msg);
+    }
+
+    #[tokio::test]
+    async fn test_add_reply_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Looking to develop solana jutsu".to_owned();
+        let reply = "All the best Naruto".to_owned();
+
+        let ixs = setup_ixs(&program_id, &payer.pubkey());
+        let add_intro_ix =
+            instruction::add_student_intro(&program_id, &payer.pubkey(), name.clone(), msg);
+
+        let mut tx1 =
+            Transaction::new_with_payer(&[ixs, vec![add_intro_ix]].concat(), Some(&payer.pubkey()));
+        tx1.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(tx1).await.unwrap();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (counter_pda, _) = instruction::find_counter_address(&program_id, &intro_pda);
+        let mut counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let mut counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert!(counter.counter == 0);
+
+        let add_reply_ix = instruction::add_reply(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            counter.counter,
+            reply,
+        );
+        let mut tx2 = Transaction::new_with_payer(&[add_reply_ix], Some(&payer.pubkey()));
+        tx2.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx2).await, Ok(_));
+
+        counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert!(counter.counter == 1);
+
+        // a supplied reply counter bump must be the canonical one
+        let reply = "Believe it".to_owned();
+        let (reply_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 1);
+        let (_, canonical_bump) = instruction::find_counter_address(&program_id, &reply_pda);
+        let bump = (0..canonical_bump)
+            .rev()
+            .find(|&bump| {
+                Pubkey::create_program_address(
+                    &[reply_pda.as_ref(), b"reply", &[bump]],
+                    &program_id,
+                )
+                .is_ok()
+            })
+            .unwrap();
+        let mut add_reply_ix =
+            instruction::add_reply(&program_id, &payer.pubkey(), &intro_pda, 1, reply.clone());
+        add_reply_ix.data = StudentIntroInstruction::AddReplyWithCounterBump {
+            reply: reply.clone(),
+            counter_bump: bump,
+        }
+        .pack();
+        let mut tx3 = Transaction::new_with_payer(&[add_reply_ix.clone()], Some(&payer.pubkey()));
+        tx3.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx3).await.is_err());
+
+        // and the original layout searches for it
+        add_reply_ix.data = StudentIntroInstruction::AddReply { reply }.pack();
+        let mut tx4 = Transaction::new_with_payer(&[add_reply_ix], Some(&payer.pubkey()));
+        tx4.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx4).await, Ok(_));
+        let reply_acc = banks_client.get_account(reply_pda).await.unwrap().unwrap();
+        let reply_data = StudentIntroReply::try_from_slice(&reply_acc.data).unwrap();
+        assert_eq!(reply_data.counter_bump, canonical_bump);
+    }
+
+    #[tokio::test]
+    async fn test_close_student_intro_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Developing solana jutsu".to_owned();
+        let destination = Pubkey::new_unique();
+
+        let ixs = setup_ixs(&program_id, &payer.pubkey());
+        let add_intro_ix =
+            instruction::add_student_intro(&program_id, &payer.pubkey(), name.clone(), msg);
+        let close_intro_ix =
+            instruction::close_student_intro(&program_id, &payer.pubkey(), &name, &destination);
+        let mut tx = Transaction::new_with_payer(
+            &[ixs, vec![add_intro_ix, close_intro_ix]].concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (counter_pda, _) = instruction::find_counter_address(&program_id, &intro_pda);
+        assert_eq!(banks_client.get_account(intro_pda).await.unwrap(), None);
+        assert_eq!(banks_client.get_account(counter_pda).await.unwrap(), None);
+        assert!(banks_client.get_balance(destination).await.unwrap() > 0);
+
+        // the counter of an intro with replies outlives it
+        let mut tx = Transaction::new_with_payer(
+            &[
+                instruction::add_student_intro(
+                    &program_id,
+                    &payer.pubkey(),
+                    name.clone(),
+                    "Back again".to_owned(),
+                ),
+                instruction::add_reply(
+                    &program_id,
+                    &payer.pubkey(),
+                    &intro_pda,
+                    0,
+                    "Welcome back".to_owned(),
+                ),
+                instruction::close_student_intro(&program_id, &payer.pubkey(), &name, &destination),
+            ],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert!(!counter.is_initialized);
+        assert_eq!(counter.counter, 1);
+
+        // and is taken over by the next intro at the same address
+        let mut tx = Transaction::new_with_payer(
+            &[
+                instruction::add_student_intro(
+                    &program_id,
+                    &payer.pubkey(),
+                    name,
+                    "Third time".to_owned(),
+                ),
+                instruction::add_reply(
+                    &program_id,
+                    &payer.pubkey(),
+                    &intro_pda,
+                    1,
+                    "Still here".to_owned(),
+                ),
+            ],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert!(counter.is_initialized);
+        assert_eq!(counter.counter, 2);
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_reply_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Looking to develop solana jutsu".to_owned();
+        let new_reply = "All the very best Naruto, believe it!".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (reply_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 0);
+        let mut tx1 = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(&program_id, &payer.pubkey(), name, msg),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "All the bst".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx1.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(tx1).await.unwrap();
+
+        let update_reply_ix =
+            instruction::update_reply(&program_id, &payer.pubkey(), &reply_pda, new_reply.clone());
+        let mut tx2 = Transaction::new_with_payer(&[update_reply_ix], Some(&payer.pubkey()));
+        tx2.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx2).await, Ok(_));
+        let reply_acc = banks_client.get_account(reply_pda).await.unwrap().unwrap();
+        assert_eq!(
+            reply_acc.data.len(),
+            StudentIntroReply::get_account_size(new_reply.clone(), None, None)
+        );
+        let reply_data = StudentIntroReply::try_from_slice(&reply_acc.data).unwrap();
+        assert_eq!(reply_data.reply, new_reply);
+
+        let delete_reply_ix = instruction::delete_reply(&program_id, &payer.pubkey(), &reply_pda);
+        let mut tx3 = Transaction::new_with_payer(&[delete_reply_ix], Some(&payer.pubkey()));
+        tx3.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx3).await, Ok(_));
+        assert_eq!(banks_client.get_account(reply_pda).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_add_reply_to_reply_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Looking to develop solana jutsu".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (parent_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 0);
+        let (child_pda, _) = instruction::find_reply_address(&program_id, &parent_pda, 0);
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(&program_id, &payer.pubkey(), name, msg),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "Which jutsu?".to_owned(),
+                    ),
+                    instruction::add_reply_to_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        &parent_pda,
+                        0,
+                        "Shadow clones".to_owned(),
+                    ),
+                    instruction::add_reply_to_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        &parent_pda,
+                        1,
+                        "Rasengan too".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let child_acc = banks_client.get_account(child_pda).await.unwrap().unwrap();
+        let child = StudentIntroReply::try_from_slice(&child_acc.data).unwrap();
+        assert_eq!(child.intro, intro_pda);
+        assert_eq!(child.parent_reply, Some(parent_pda));
+        assert_eq!(child.reply, "Shadow clones");
+        let (counter_pda, _) = instruction::find_counter_address(&program_id, &parent_pda);
+        let counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert_eq!(counter.counter, 2);
+
+        let delete_reply_ix = instruction::delete_reply(&program_id, &payer.pubkey(), &parent_pda);
+        let mut tx = Transaction::new_with_payer(&[delete_reply_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(parent_pda).await.unwrap(), None);
+        assert_eq!(banks_client.get_account(counter_pda).await.unwrap(), None);
+        assert!(banks_client.get_account(child_pda).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_self_reply_not_rewarded_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Looking to develop solana jutsu".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(&program_id, &payer.pubkey(), name, msg),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "Replying to myself".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let ata = get_associated_token_address(&payer.pubkey(), &find_mint_address(&program_id).0);
+        let ata_acc = banks_client.get_account(ata).await.unwrap().unwrap();
+        let token_acc = spl_token::state::Account::unpack(&ata_acc.data).unwrap();
+        assert_eq!(token_acc.amount, ConfigParams::default().intro_reward);
+
+        let (record_pda, _) =
+            instruction::find_replier_record_address(&program_id, &intro_pda, &payer.pubkey());
+        let record_acc = banks_client.get_account(record_pda).await.unwrap().unwrap();
+        let record = ReplierRecord::try_from_slice(&record_acc.data).unwrap();
+        assert_eq!(record.rewarded_replies, 0);
+    }
+
+    #[tokio::test]
+    async fn test_user_profile_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        name.clone(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        "Hokage".to_owned(),
+                        "Protecting the leaf".to_owned(),
+                    ),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "Believe it".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let (profile_pda, _) = instruction::find_user_profile_address(&program_id, &payer.pubkey());
+        let profile_acc = banks_client
+            .get_account(profile_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let profile = UserProfile::try_from_slice(&profile_acc.data).unwrap();
+        assert_eq!(profile.wallet, payer.pubkey());
+        assert_eq!(profile.display_name, name);
+        assert_eq!(profile.total_intros, 2);
+        assert_eq!(profile.total_replies, 1);
+        assert_eq!(
+            profile.total_tokens_earned,
+            2 * ConfigParams::default().intro_reward
+        );
+    }
+
+    #[tokio::test]
+    async fn test_one_intro_per_wallet_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let params = ConfigParams {
+            one_intro_per_wallet: true,
+            ..ConfigParams::default()
+        };
+
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::update_config(
+                        &program_id,
+                        &payer.pubkey(),
+                        &payer.pubkey(),
+                        params,
+                    ),
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        "Naruto".to_owned(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let (marker_pda, _) = instruction::find_intro_marker_address(&program_id, &payer.pubkey());
+        let marker_acc = banks_client.get_account(marker_pda).await.unwrap().unwrap();
+        let marker = IntroMarker::try_from_slice(&marker_acc.data).unwrap();
+        let (intro_pda, _) =
+            instruction::find_intro_address(&program_id, &payer.pubkey(), "Naruto");
+        assert_eq!(marker.intro, intro_pda);
+
+        // a second name does not get the wallet another intro
+        let add_intro_ix = instruction::add_student_intro(
+            &program_id,
+            &payer.pubkey(),
+            "Hokage".to_owned(),
+            "Protecting the leaf".to_owned(),
         );
-        let ata = get_associated_token_address(&payer.pubkey(), &mint);
+        let mut tx = Transaction::new_with_payer(&[add_intro_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+    }
 
-        let mut data = vec![2];
-        data.append(
-            &mut (TryInto::<u32>::try_into(reply.len()).unwrap().to_le_bytes())
-                .try_into()
-                .unwrap(),
+    #[tokio::test]
+    async fn test_rename_student_intro_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Narutoo".to_owned();
+        let new_name = "Naruto".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (new_intro_pda, _) =
+            instruction::find_intro_address(&program_id, &payer.pubkey(), &new_name);
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        name.clone(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "Typo in my name".to_owned(),
+                    ),
+                    instruction::react(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        ReactionKind::Like,
+                    ),
+                    instruction::rename_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        name,
+                        new_name.clone(),
+                    ),
+                    instruction::add_reply_to_renamed_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        &new_intro_pda,
+                        &intro_pda,
+                        1,
+                        "Fixed it".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
         );
-        data.append(&mut reply.into_bytes());
-        let add_reply_ix = Instruction {
-            program_id,
-            accounts: vec![
-                AccountMeta::new_readonly(payer.pubkey(), true),
-                AccountMeta::new_readonly(intro_pda, false),
-                AccountMeta::new(counter_pda, false),
-                AccountMeta::new(reply_pda, false),
-                AccountMeta::new(mint, false),
-                AccountMeta::new_readonly(mint_auth, false),
-                AccountMeta::new(ata, false),
-                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
-                AccountMeta::new_readonly(TOKEN_PROGRAM_ID, false),
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        assert_eq!(banks_client.get_account(intro_pda).await.unwrap(), None);
+        let intro_acc = banks_client
+            .get_account(new_intro_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.name, new_name);
+        assert_eq!(intro.origin, Some(intro_pda));
+
+        let (counter_pda, _) = instruction::find_counter_address(&program_id, &intro_pda);
+        let counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert_eq!(counter.counter, 2);
+        let (reply_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 1);
+        let reply_acc = banks_client.get_account(reply_pda).await.unwrap().unwrap();
+        let reply = StudentIntroReply::try_from_slice(&reply_acc.data).unwrap();
+        assert_eq!(reply.intro, intro_pda);
+        assert_eq!(intro.reaction_counts[ReactionKind::Like as usize], 1);
+        let (marker_pda, _) = instruction::find_intro_marker_address(&program_id, &payer.pubkey());
+        let marker_acc = banks_client.get_account(marker_pda).await.unwrap().unwrap();
+        let marker = IntroMarker::try_from_slice(&marker_acc.data).unwrap();
+        assert_eq!(marker.intro, new_intro_pda);
+
+        let (reaction_pda, _) =
+            instruction::find_reaction_address(&program_id, &intro_pda, &payer.pubkey());
+        let unreact_ix = instruction::unreact_from_renamed_intro(
+            &program_id,
+            &payer.pubkey(),
+            &new_intro_pda,
+            &intro_pda,
+        );
+        let mut tx = Transaction::new_with_payer(&[unreact_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(reaction_pda).await.unwrap(), None);
+        let intro_acc = banks_client
+            .get_account(new_intro_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.reaction_counts[ReactionKind::Like as usize], 0);
+    }
+
+    #[tokio::test]
+    async fn test_moderation_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let outsider = Keypair::new();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (counter_pda, _) = instruction::find_counter_address(&program_id, &intro_pda);
+        let (reply_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 0);
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        name,
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                    instruction::add_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        0,
+                        "Posted in time".to_owned(),
+                    ),
+                    instruction::add_reply_to_reply(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        &reply_pda,
+                        0,
+                        "Just in time".to_owned(),
+                    ),
+                    // the admin is always a moderator
+                    instruction::hide_content(&program_id, &payer.pubkey(), &intro_pda),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert!(intro.hidden);
+
+        let add_reply_ix = instruction::add_reply(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            1,
+            "Hidden from view".to_owned(),
+        );
+        let mut tx = Transaction::new_with_payer(&[add_reply_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+        let add_reply_ix = instruction::add_reply_to_reply(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            &reply_pda,
+            1,
+            "Hidden thread".to_owned(),
+        );
+        let mut tx = Transaction::new_with_payer(&[add_reply_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+
+        let unhide_ix = instruction::unhide_content(&program_id, &outsider.pubkey(), &intro_pda);
+        let mut tx = Transaction::new_with_payer(&[unhide_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &outsider], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+
+        let (reply_counter_pda, _) = instruction::find_counter_address(&program_id, &reply_pda);
+        let force_close_ix = instruction::force_close(
+            &program_id,
+            &payer.pubkey(),
+            &reply_pda,
+            &payer.pubkey(),
+            &reply_counter_pda,
+        );
+        let mut tx = Transaction::new_with_payer(&[force_close_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(reply_pda).await.unwrap(), None);
+        assert_eq!(
+            banks_client.get_account(reply_counter_pda).await.unwrap(),
+            None
+        );
+
+        let force_close_ix = instruction::force_close(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            &payer.pubkey(),
+            &counter_pda,
+        );
+        let mut tx = Transaction::new_with_payer(&[force_close_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(intro_pda).await.unwrap(), None);
+        // the intro got a reply, so its counter stays detached
+        let counter_acc = banks_client
+            .get_account(counter_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data).unwrap();
+        assert!(!counter.is_initialized);
+    }
+
+    #[tokio::test]
+    async fn test_report_content_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let params = ConfigParams {
+            report_threshold: 2,
+            ..ConfigParams::default()
+        };
+        let (first, second) = (Keypair::new(), Keypair::new());
+
+        let (intro_pda, _) =
+            instruction::find_intro_address(&program_id, &payer.pubkey(), "Naruto");
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::update_config(
+                        &program_id,
+                        &payer.pubkey(),
+                        &payer.pubkey(),
+                        params,
+                    ),
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        "Naruto".to_owned(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                    system_instruction::transfer(&payer.pubkey(), &first.pubkey(), 1_000_000_000),
+                    system_instruction::transfer(&payer.pubkey(), &second.pubkey(), 1_000_000_000),
+                    instruction::report_content(
+                        &program_id,
+                        &first.pubkey(),
+                        &intro_pda,
+                        ReportReason::Spam,
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer, &first], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.report_count, 1);
+        assert!(!intro.hidden);
+        let (report_pda, _) =
+            instruction::find_report_address(&program_id, &intro_pda, &first.pubkey());
+        let report_acc = banks_client.get_account(report_pda).await.unwrap().unwrap();
+        let report = ContentReport::try_from_slice(&report_acc.data).unwrap();
+        assert_eq!(report.reason, ReportReason::Spam);
+
+        // a user reports a target once
+        let report_ix = instruction::report_content(
+            &program_id,
+            &first.pubkey(),
+            &intro_pda,
+            ReportReason::Other,
+        );
+        let mut tx = Transaction::new_with_payer(&[report_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &first], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+
+        // renaming the intro keeps its reports
+        let (renamed_pda, _) =
+            instruction::find_intro_address(&program_id, &payer.pubkey(), "Naruto Uzumaki");
+        let rename_ix = instruction::rename_student_intro(
+            &program_id,
+            &payer.pubkey(),
+            "Naruto".to_owned(),
+            "Naruto Uzumaki".to_owned(),
+        );
+        let mut tx = Transaction::new_with_payer(&[rename_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let report_ix = instruction::report_renamed_intro(
+            &program_id,
+            &first.pubkey(),
+            &renamed_pda,
+            &intro_pda,
+            ReportReason::Other,
+        );
+        let mut tx = Transaction::new_with_payer(&[report_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &first], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+
+        let report_ix = instruction::report_renamed_intro(
+            &program_id,
+            &second.pubkey(),
+            &renamed_pda,
+            &intro_pda,
+            ReportReason::Harassment,
+        );
+        let mut tx = Transaction::new_with_payer(&[report_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &second], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client
+            .get_account(renamed_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.report_count, 2);
+        assert!(intro.hidden);
+    }
+
+    #[tokio::test]
+    async fn test_cohort_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let cohort_name = "Class of 2026";
+        let params = CohortParams {
+            open: true,
+            intro_reward: Some(1),
+            reply_reward: None,
+        };
+
+        let (cohort_pda, _) = instruction::find_cohort_address(&program_id, cohort_name);
+        let (intro_pda, _) = instruction::find_cohort_intro_address(
+            &program_id,
+            &cohort_pda,
+            &payer.pubkey(),
+            "Naruto",
+        );
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::create_cohort(
+                        &program_id,
+                        &payer.pubkey(),
+                        cohort_name.to_owned(),
+                        &payer.pubkey(),
+                        params.clone(),
+                    ),
+                    instruction::add_cohort_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        &cohort_pda,
+                        "Naruto".to_owned(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.cohort, Some(cohort_pda));
+        // the cohort's intro reward overrides the config's
+        let ata = get_associated_token_address(&payer.pubkey(), &find_mint_address(&program_id).0);
+        let ata_acc = banks_client.get_account(ata).await.unwrap().unwrap();
+        let token_acc = spl_token::state::Account::unpack(&ata_acc.data).unwrap();
+        assert_eq!(token_acc.amount, 1);
+
+        // replies in the cohort's thread need the cohort
+        let add_reply_ix = instruction::add_reply(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            0,
+            "Welcome to the class".to_owned(),
+        );
+        let mut tx =
+            Transaction::new_with_payer(std::slice::from_ref(&add_reply_ix), Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+        let mut tx = Transaction::new_with_payer(
+            &[instruction::with_cohort(add_reply_ix, &cohort_pda)],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let (reply_pda, _) = instruction::find_reply_address(&program_id, &intro_pda, 0);
+        let reply_acc = banks_client.get_account(reply_pda).await.unwrap().unwrap();
+        let reply = StudentIntroReply::try_from_slice(&reply_acc.data).unwrap();
+        assert_eq!(reply.cohort, Some(cohort_pda));
+
+        let (renamed_pda, _) = instruction::find_cohort_intro_address(
+            &program_id,
+            &cohort_pda,
+            &payer.pubkey(),
+            "Naruto Uzumaki",
+        );
+        let mut tx = Transaction::new_with_payer(
+            &[
+                instruction::update_cohort_intro(
+                    &program_id,
+                    &cohort_pda,
+                    &payer.pubkey(),
+                    "Naruto".to_owned(),
+                    "Mastering solana jutsu".to_owned(),
+                ),
+                instruction::rename_cohort_intro(
+                    &program_id,
+                    &cohort_pda,
+                    &payer.pubkey(),
+                    "Naruto".to_owned(),
+                    "Naruto Uzumaki".to_owned(),
+                ),
+            ],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client
+            .get_account(renamed_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.msg, "Mastering solana jutsu");
+        assert_eq!(intro.cohort, Some(cohort_pda));
+        let (marker_pda, _) = instruction::find_cohort_intro_marker_address(
+            &program_id,
+            &cohort_pda,
+            &payer.pubkey(),
+        );
+        let marker_acc = banks_client.get_account(marker_pda).await.unwrap().unwrap();
+        let marker = IntroMarker::try_from_slice(&marker_acc.data).unwrap();
+        assert_eq!(marker.intro, renamed_pda);
+
+        let close_ix = instruction::close_renamed_cohort_intro(
+            &program_id,
+            &cohort_pda,
+            &payer.pubkey(),
+            "Naruto Uzumaki",
+            &intro_pda,
+            &payer.pubkey(),
+        );
+        let mut tx = Transaction::new_with_payer(&[close_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(renamed_pda).await.unwrap(), None);
+
+        // a closed cohort takes no intros
+        let mut tx = Transaction::new_with_payer(
+            &[
+                instruction::update_cohort(
+                    &program_id,
+                    &payer.pubkey(),
+                    cohort_name,
+                    &payer.pubkey(),
+                    CohortParams {
+                        open: false,
+                        ..params
+                    },
+                ),
+                instruction::add_cohort_intro(
+                    &program_id,
+                    &payer.pubkey(),
+                    &cohort_pda,
+                    "Hokage".to_owned(),
+                    "Protecting the leaf".to_owned(),
+                ),
+            ],
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_react_and_unreact_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        let msg = "Looking to develop solana jutsu".to_owned();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (reaction_pda, _) =
+            instruction::find_reaction_address(&program_id, &intro_pda, &payer.pubkey());
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    instruction::add_student_intro(&program_id, &payer.pubkey(), name, msg),
+                    instruction::react(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        ReactionKind::Love,
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.reaction_counts, [0, 1, 0, 0]);
+
+        // a second reaction to the same intro is rejected
+        let react_ix =
+            instruction::react(&program_id, &payer.pubkey(), &intro_pda, ReactionKind::Like);
+        let mut tx = Transaction::new_with_payer(&[react_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+
+        let unreact_ix = instruction::unreact(&program_id, &payer.pubkey(), &intro_pda);
+        let mut tx = Transaction::new_with_payer(&[unreact_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(banks_client.get_account(reaction_pda).await.unwrap(), None);
+        let intro_acc = banks_client.get_account(intro_pda).await.unwrap().unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.reaction_counts, [0; ReactionKind::COUNT]);
+    }
+
+    #[tokio::test]
+    async fn test_reactions_to_closed_intro_ix() {
+        let program_id = Pubkey::new_unique();
+        let mut context = start_with_context(program_id).await;
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        let other = Keypair::new();
+        let name = "Naruto".to_owned();
+        let destination = Pubkey::new_unique();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let (other_reaction_pda, _) =
+            instruction::find_reaction_address(&program_id, &intro_pda, &other.pubkey());
+        let mut tx = Transaction::new_with_payer(
+            &[
+                setup_ixs(&program_id, &payer.pubkey()),
+                vec![
+                    system_instruction::transfer(&payer.pubkey(), &other.pubkey(), 1_000_000_000),
+                    instruction::add_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        name.clone(),
+                        "Developing solana jutsu".to_owned(),
+                    ),
+                    instruction::react(
+                        &program_id,
+                        &payer.pubkey(),
+                        &intro_pda,
+                        ReactionKind::Love,
+                    ),
+                    instruction::react(
+                        &program_id,
+                        &other.pubkey(),
+                        &intro_pda,
+                        ReactionKind::Celebrate,
+                    ),
+                    instruction::close_student_intro(
+                        &program_id,
+                        &payer.pubkey(),
+                        &name,
+                        &destination,
+                    ),
+                ],
+            ]
+            .concat(),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer, &other], context.last_blockhash);
+        assert_matches!(context.banks_client.process_transaction(tx).await, Ok(_));
+
+        // the intro posted again at the address is told apart by its slot
+        context.warp_to_slot(100).unwrap();
+        let recent_blockhash = context.banks_client.get_latest_blockhash().await.unwrap();
+        let mut tx = Transaction::new_with_payer(
+            &[
+                instruction::add_student_intro(
+                    &program_id,
+                    &payer.pubkey(),
+                    name,
+                    "Back again".to_owned(),
+                ),
+                instruction::react(&program_id, &payer.pubkey(), &intro_pda, ReactionKind::Like),
             ],
-            data,
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(context.banks_client.process_transaction(tx).await, Ok(_));
+        let intro_acc = context
+            .banks_client
+            .get_account(intro_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.reaction_counts, [1, 0, 0, 0]);
+
+        // removing a reaction left on the closed intro leaves the counts alone
+        let unreact_ix = instruction::unreact(&program_id, &other.pubkey(), &intro_pda);
+        let mut tx = Transaction::new_with_payer(&[unreact_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &other], recent_blockhash);
+        assert_matches!(context.banks_client.process_transaction(tx).await, Ok(_));
+        assert_eq!(
+            context
+                .banks_client
+                .get_account(other_reaction_pda)
+                .await
+                .unwrap(),
+            None
+        );
+        let intro_acc = context
+            .banks_client
+            .get_account(intro_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(intro.reaction_counts, [1, 0, 0, 0]);
+    }
+
+    #[tokio::test]
+    async fn test_update_config_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let new_admin = Pubkey::new_unique();
+        let params = ConfigParams {
+            intro_reward: 1,
+            reply_reward: 2,
+            max_intro_size: 500,
+            mint_decimals: 6,
+            ..ConfigParams::default()
         };
 
-        let mut tx2 = Transaction::new_with_payer(&[add_reply_ix], Some(&payer.pubkey()));
-        tx2.sign(&[&payer], recent_blockhash);
-        assert_matches!(banks_client.process_transaction(tx2).await, Ok(_));
+        let init_config_ix =
+            instruction::initialize_config(&program_id, &payer.pubkey(), ConfigParams::default());
+        let update_config_ix =
+            instruction::update_config(&program_id, &payer.pubkey(), &new_admin, params.clone());
+        let mut tx =
+            Transaction::new_with_payer(&[init_config_ix, update_config_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
 
-        counter_acc = banks_client
-            .get_account(counter_pda)
+        let (config_pda, _) = instruction::find_config_address(&program_id);
+        let config_acc = banks_client.get_account(config_pda).await.unwrap().unwrap();
+        let config = ProgramConfig::try_from_slice(&config_acc.data).unwrap();
+        assert_eq!(config.admin, new_admin);
+        assert_eq!(config.intro_reward, params.intro_reward);
+        assert_eq!(config.max_intro_size, params.max_intro_size);
+
+        // the previous admin no longer controls the config
+        let update_config_ix =
+            instruction::update_config(&program_id, &payer.pubkey(), &payer.pubkey(), params);
+        let mut tx = Transaction::new_with_payer(&[update_config_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_init_config_requires_upgrade_authority_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let impostor = Keypair::new();
+
+        let init_config_ix = instruction::initialize_config(
+            &program_id,
+            &impostor.pubkey(),
+            ConfigParams::default(),
+        );
+        let mut tx = Transaction::new_with_payer(&[init_config_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer, &impostor], recent_blockhash);
+        assert!(banks_client.process_transaction(tx).await.is_err());
+    }
+
+    // realloc is only supported when the program runs as BPF
+    #[cfg(feature = "test-bpf")]
+    #[tokio::test]
+    async fn test_migrate_account_ix() {
+        let program_id = Pubkey::new_unique();
+        let mut pt = program_test(program_id);
+        let intro = Pubkey::new_unique();
+        let identity = Pubkey::new_unique();
+        // legacy intro: String discriminator in a fixed 1000 byte account
+        let mut legacy_data = (
+            "intro".to_owned(),
+            true,
+            identity,
+            "Naruto".to_owned(),
+            "Hi".to_owned(),
+        )
+            .try_to_vec()
+            .unwrap();
+        legacy_data.resize(1000, 0);
+        pt.add_account(
+            intro,
+            Account {
+                lamports: Rent::default().minimum_balance(1000),
+                data: legacy_data,
+                owner: program_id,
+                ..Account::default()
+            },
+        );
+        let (mut banks_client, payer, recent_blockhash) = pt.start().await;
+
+        let migrate_ix = instruction::migrate_account(&program_id, &payer.pubkey(), &intro);
+        let mut tx = Transaction::new_with_payer(&[migrate_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        assert_matches!(banks_client.process_transaction(tx).await, Ok(_));
+
+        let intro_acc = banks_client.get_account(intro).await.unwrap().unwrap();
+        assert_eq!(
+            intro_acc.data.len(),
+            StudentIntroAccountState::get_account_size(
+                "Naruto".to_owned(),
+                "Hi".to_owned(),
+                None,
+                None
+            )
+        );
+        let intro_data = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        assert_eq!(
+            intro_data.discriminator,
+            StudentIntroAccountState::DISCRIMINATOR
+        );
+        assert_eq!(intro_data.version, ACCOUNT_VERSION);
+        assert_eq!(intro_data.identity, identity);
+        assert_eq!(intro_data.name, "Naruto");
+        assert_eq!(intro_data.msg, "Hi");
+    }
+
+    // compute units are only metered when the program runs as BPF
+    #[cfg(feature = "test-bpf")]
+    #[tokio::test]
+    async fn test_add_reply_compute_units_ix() {
+        let program_id = Pubkey::new_unique();
+        let mut context = start_with_context(program_id).await;
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        let recent_blockhash = context.last_blockhash;
+        let name = "Naruto".to_owned();
+        let ixs = setup_ixs(&program_id, &payer.pubkey());
+        let add_intro_ix = instruction::add_student_intro(
+            &program_id,
+            &payer.pubkey(),
+            name.clone(),
+            "Developing solana jutsu".to_owned(),
+        );
+        let mut tx =
+            Transaction::new_with_payer(&[ixs, vec![add_intro_ix]].concat(), Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let add_reply_ix = instruction::add_reply(
+            &program_id,
+            &payer.pubkey(),
+            &intro_pda,
+            0,
+            "All the best Naruto".to_owned(),
+        );
+        let stored = compute_units(
+            &mut context.banks_client,
+            &payer,
+            add_reply_ix.clone(),
+            recent_blockhash,
+        )
+        .await;
+
+        // rewrite the intro and config without bumps, making add_reply search
+        // them as it did before they were stored
+        let (config_pda, _) = instruction::find_config_address(&program_id);
+        let mut intro_acc = context
+            .banks_client
+            .get_account(intro_pda)
             .await
             .unwrap()
             .unwrap();
-        counter = StudentIntroReplyCounter::try_from_slice(&counter_acc.data.borrow()).unwrap();
-        assert!(counter.counter == 1);
+        let intro = StudentIntroAccountState::try_from_slice(&intro_acc.data).unwrap();
+        intro_acc.data = (
+            intro.discriminator,
+            2u8,
+            intro.is_initialized,
+            intro.identity,
+            intro.name,
+            intro.msg,
+        )
+            .try_to_vec()
+            .unwrap();
+        context.set_account(&intro_pda, &AccountSharedData::from(intro_acc));
+        let mut config_acc = context
+            .banks_client
+            .get_account(config_pda)
+            .await
+            .unwrap()
+            .unwrap();
+        let config = ProgramConfig::try_from_slice(&config_acc.data).unwrap();
+        config_acc.data = (
+            config.discriminator,
+            2u8,
+            config.is_initialized,
+            config.admin,
+            config.intro_reward,
+            config.reply_reward,
+            config.max_intro_size,
+            config.mint_decimals,
+        )
+            .try_to_vec()
+            .unwrap();
+        context.set_account(&config_pda, &AccountSharedData::from(config_acc));
+        let searched = compute_units(
+            &mut context.banks_client,
+            &payer,
+            add_reply_ix,
+            recent_blockhash,
+        )
+        .await;
+
+        assert!(stored < searched);
+    }
+
+    #[cfg(feature = "test-bpf")]
+    #[tokio::test]
+    async fn test_compute_unit_budgets_ix() {
+        let program_id = Pubkey::new_unique();
+        let (mut banks_client, payer, recent_blockhash) = start(program_id).await;
+        let name = "Naruto".to_owned();
+        // the largest intro the default config accepts
+        let msg_len = ConfigParams::default().max_intro_size as usize
+            - StudentIntroAccountState::get_account_size(name.clone(), String::new(), None, None);
+        let add_intro_ix = instruction::add_student_intro(
+            &program_id,
+            &payer.pubkey(),
+            name.clone(),
+            "x".repeat(msg_len),
+        );
+        let mut tx = Transaction::new_with_payer(
+            &setup_ixs(&program_id, &payer.pubkey()),
+            Some(&payer.pubkey()),
+        );
+        tx.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        let intro_units = compute_units(
+            &mut banks_client,
+            &payer,
+            add_intro_ix.clone(),
+            recent_blockhash,
+        )
+        .await;
+        let mut tx = Transaction::new_with_payer(&[add_intro_ix], Some(&payer.pubkey()));
+        tx.sign(&[&payer], recent_blockhash);
+        banks_client.process_transaction(tx).await.unwrap();
+
+        let (intro_pda, _) = instruction::find_intro_address(&program_id, &payer.pubkey(), &name);
+        let add_reply_ix =
+            instruction::add_reply(&program_id, &payer.pubkey(), &intro_pda, 0, "x".repeat(500));
+        let reply_units =
+            compute_units(&mut banks_client, &payer, add_reply_ix, recent_blockhash).await;
+
+        assert!(intro_units <= instruction::ADD_STUDENT_INTRO_COMPUTE_UNITS as u64);
+        assert!(reply_units <= instruction::ADD_REPLY_COMPUTE_UNITS as u64);
     }
 }
@@ -1,33 +1,243 @@
+use crate::error::StudentIntroError;
+use crate::instruction::{
+    find_config_address, find_counter_address, find_intro_address, find_mint_address,
+    find_mint_auth_address, find_reply_address, CohortParams, ConfigParams,
+};
+use crate::validation::TextLimits;
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
+    account_info::AccountInfo,
+    msg,
+    program_error::ProgramError,
     program_pack::{IsInitialized, Sealed},
     pubkey::Pubkey,
 };
 
+/// Layout version written by this program. Version 1 accounts have no version
+/// byte, their `is_initialized` flag (always 1) sits in its place. Versions
+/// before 3 do not store PDA bumps, replies before 4 cannot be threaded and
+/// intros and replies before 5 have no reaction counts. Configs before 6 have
+/// no anti-abuse rules and configs before 7 cannot limit wallets to one intro.
+/// Intros before 8 cannot be renamed and configs before 9 have no text limits.
+/// Intros and replies before 10 cannot be hidden and configs before 10 have no
+/// moderators. Intros and replies before 11 have no report counts and configs
+/// before 11 cannot hide reported content. Intros and replies before 12 cannot
+/// be posted in a cohort. Intros and reactions before 13 do not record the slot
+/// the intro was posted in.
+pub const ACCOUNT_VERSION: u8 = 13;
+
+/// Moderator slots in the config, unused slots hold the default pubkey.
+pub const MAX_MODERATORS: usize = 5;
+
+/// Length of the day that daily reward caps apply to.
+pub const SECONDS_PER_DAY: i64 = 24 * 60 * 60;
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct StudentIntroAccountState {
-    pub discriminator: String,
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub is_initialized: bool,
+    /// Set by moderators, clients should not display hidden intros.
+    pub hidden: bool,
+    /// Reports received, the intro is hidden once they reach the config's
+    /// report threshold.
+    pub report_count: u32,
+    pub bump: u8,
+    /// Bump of the intro's reply counter PDA.
+    pub counter_bump: u8,
+    /// Number of reactions of each kind, indexed by `ReactionKind`.
+    pub reaction_counts: [u64; ReactionKind::COUNT],
     pub identity: Pubkey,
     pub name: String,
     pub msg: String,
+    /// Address the intro was first posted at, which its reply counter and
+    /// replies stay seeded under. `None` until the intro is renamed.
+    pub origin: Option<Pubkey>,
+    /// Cohort the intro was posted in, the first seed of its PDA. `None` for
+    /// intros on the global board.
+    pub cohort: Option<Pubkey>,
+    /// Slot the intro was posted in, telling it apart from an earlier intro
+    /// closed at the same address. 0 for intros posted before version 13.
+    pub created_slot: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct StudentIntroReplyCounter {
-    pub discriminator: String,
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub is_initialized: bool,
     pub counter: u64,
 }
 
 #[derive(BorshSerialize, BorshDeserialize)]
 pub struct StudentIntroReply {
-    pub discriminator: String,
+    pub discriminator: [u8; 8],
+    pub version: u8,
     pub is_initialized: bool,
+    /// Set by moderators, clients should not display hidden replies.
+    pub hidden: bool,
+    /// Reports received, the reply is hidden once they reach the config's
+    /// report threshold.
+    pub report_count: u32,
+    pub bump: u8,
+    /// Bump of the counter PDA of replies posted under this reply.
+    pub counter_bump: u8,
+    /// Number of reactions of each kind, indexed by `ReactionKind`.
+    pub reaction_counts: [u64; ReactionKind::COUNT],
+    /// Intro the thread belongs to.
     pub intro: Pubkey,
+    /// Reply this one answers, `None` when posted directly under the intro.
+    pub parent_reply: Option<Pubkey>,
     pub replier: Pubkey,
     pub reply: String,
     pub count: u64,
+    /// Cohort of the thread's intro, `None` on the global board.
+    pub cohort: Option<Pubkey>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ProgramConfig {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub mint_bump: u8,
+    pub mint_auth_bump: u8,
+    pub admin: Pubkey,
+    /// Tokens minted per intro, in base units of the mint.
+    pub intro_reward: u64,
+    /// Tokens minted per reply, in base units of the mint.
+    pub reply_reward: u64,
+    pub max_intro_size: u32,
+    pub mint_decimals: u8,
+    /// Whether replies to one's own intro or reply are rewarded.
+    pub reward_self_replies: bool,
+    /// Replies of one replier rewarded under the same intro.
+    pub max_rewarded_replies_per_intro: u32,
+    /// Tokens a wallet can be rewarded per day, in base units of the mint.
+    pub daily_reward_cap: u64,
+    /// Whether a wallet may post a single intro, instead of one per name.
+    pub one_intro_per_wallet: bool,
+    pub name_limits: TextLimits,
+    pub msg_limits: TextLimits,
+    pub reply_limits: TextLimits,
+    /// Wallets allowed to hide and force close content besides the admin.
+    pub moderators: [Pubkey; MAX_MODERATORS],
+    /// Reports that hide an intro or reply, 0 disables hiding on reports.
+    pub report_threshold: u32,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReactionKind {
+    Like,
+    Love,
+    Celebrate,
+    Insightful,
+}
+
+/// A user's reaction to an intro or reply, one per (target, user) pair.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct StudentIntroReaction {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    /// Reply reacted to, or the thread of the intro reacted to.
+    pub target: Pubkey,
+    pub user: Pubkey,
+    pub kind: ReactionKind,
+    /// `created_slot` of the intro reacted to, a reaction left on an intro
+    /// closed since no longer counts for the next one at its address. 0 for
+    /// replies, whose addresses are not reused.
+    pub target_slot: u64,
+}
+
+/// Replies of one replier rewarded under one intro, threads included.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ReplierRecord {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub rewarded_replies: u32,
+}
+
+/// Rewards minted to one wallet during the current day.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct RewardTracker {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    /// Days since the unix epoch.
+    pub day: i64,
+    pub rewarded_today: u64,
+}
+
+/// Per wallet stats, created with the wallet's first intro or reply.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct UserProfile {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub wallet: Pubkey,
+    /// Name of the wallet's first intro, empty until it posts one.
+    pub display_name: String,
+    pub total_intros: u64,
+    pub total_replies: u64,
+    /// Tokens minted to the wallet, in base units of the mint.
+    pub total_tokens_earned: u64,
+    /// Unix timestamp of the profile's creation.
+    pub joined_at: i64,
+}
+
+/// Marks that a wallet posted an intro, recording the first one it posted.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct IntroMarker {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub intro: Pubkey,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportReason {
+    Spam,
+    Harassment,
+    Inappropriate,
+    Other,
+}
+
+/// A user's report of an intro or reply, one per (reporter, target) pair.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct ContentReport {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    /// Reply reported, or the thread of the intro reported.
+    pub target: Pubkey,
+    pub reporter: Pubkey,
+    pub reason: ReportReason,
+}
+
+/// A class with its own board of intros and replies, managed by its admin.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct Cohort {
+    pub discriminator: [u8; 8],
+    pub version: u8,
+    pub is_initialized: bool,
+    pub bump: u8,
+    pub admin: Pubkey,
+    pub name: String,
+    /// Whether intros and replies may be posted in the cohort.
+    pub open: bool,
+    /// Overrides the config's intro reward, in base units of the mint.
+    pub intro_reward: Option<u64>,
+    /// Overrides the config's reply reward, in base units of the mint.
+    pub reply_reward: Option<u64>,
 }
 
 impl Sealed for StudentIntroAccountState {}
@@ -47,19 +257,1830 @@ impl IsInitialized for StudentIntroReply {
         self.is_initialized
     }
 }
+impl IsInitialized for ProgramConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for StudentIntroReaction {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for ReplierRecord {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for RewardTracker {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for UserProfile {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for IntroMarker {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for ContentReport {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl IsInitialized for Cohort {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+impl StudentIntroAccountState {
+    pub fn get_account_size(
+        name: String,
+        msg: String,
+        origin: Option<Pubkey>,
+        cohort: Option<Pubkey>,
+    ) -> usize {
+        Self::DISCRIMINATOR.len()
+            + 1
+            + 1
+            + 1
+            + 4
+            + 2
+            + 8 * ReactionKind::COUNT
+            + 32
+            + (4 + name.len())
+            + (4 + msg.len())
+            + (1 + origin.map_or(0, |_| 32))
+            + (1 + cohort.map_or(0, |_| 32))
+            + 8
+    }
+
+    /// The address replies to the intro at `address` are seeded under.
+    pub fn thread<'a>(&'a self, address: &'a Pubkey) -> &'a Pubkey {
+        self.origin.as_ref().unwrap_or(address)
+    }
+}
 impl StudentIntroAccountState {
-    pub const DISCRIMINATOR: &'static str = "intro";
-    pub fn get_account_size(name: String, msg: String) -> usize {
-        (4 + Self::DISCRIMINATOR.len()) + 1 + (4 + name.len()) + (4 + msg.len())
+    /// Searches the canonical intro and counter bumps, for accounts converted
+    /// from a layout that did not store them.
+    pub fn find_bumps(&mut self, program_id: &Pubkey) {
+        let (intro, bump) = find_intro_address(program_id, &self.identity, &self.name);
+        self.bump = bump;
+        self.counter_bump = find_counter_address(program_id, &intro).1;
     }
 }
 impl StudentIntroReplyCounter {
-    pub const DISCRIMINATOR: &'static str = "counter";
-    pub const SIZE: usize = (4 + Self::DISCRIMINATOR.len()) + 1 + 8;
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 8;
 }
 impl StudentIntroReply {
-    pub const DISCRIMINATOR: &'static str = "reply";
-    pub fn get_account_size(reply: String) -> usize {
-        (4 + Self::DISCRIMINATOR.len()) + 1 + 32 + 32 + (4 + reply.len()) + 8
+    pub fn get_account_size(
+        reply: String,
+        parent_reply: Option<Pubkey>,
+        cohort: Option<Pubkey>,
+    ) -> usize {
+        Self::DISCRIMINATOR.len()
+            + 1
+            + 1
+            + 1
+            + 4
+            + 2
+            + 8 * ReactionKind::COUNT
+            + 32
+            + (1 + parent_reply.map_or(0, |_| 32))
+            + 32
+            + (4 + reply.len())
+            + 8
+            + (1 + cohort.map_or(0, |_| 32))
+    }
+
+    /// The intro or reply this reply was posted under, the first seed of its PDA.
+    pub fn parent(&self) -> &Pubkey {
+        self.parent_reply.as_ref().unwrap_or(&self.intro)
+    }
+
+    /// Searches the canonical reply and child counter bumps, for accounts
+    /// converted from a layout that did not store them.
+    pub fn find_bumps(&mut self, program_id: &Pubkey) {
+        let (reply, bump) = find_reply_address(program_id, self.parent(), self.count);
+        self.bump = bump;
+        self.counter_bump = find_counter_address(program_id, &reply).1;
+    }
+}
+impl ProgramConfig {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len()
+        + 1
+        + 1
+        + 3
+        + 32
+        + 8
+        + 8
+        + 4
+        + 1
+        + 1
+        + 4
+        + 8
+        + 1
+        + 3 * 8
+        + 32 * MAX_MODERATORS
+        + 4;
+
+    /// Whether `key` may moderate content, the admin always can.
+    pub fn is_moderator(&self, key: &Pubkey) -> bool {
+        *key == self.admin || (*key != Pubkey::default() && self.moderators.contains(key))
+    }
+
+    /// Searches the canonical config, mint and mint authority bumps, for
+    /// accounts converted from a layout that did not store them.
+    pub fn find_bumps(&mut self, program_id: &Pubkey) {
+        self.bump = find_config_address(program_id).1;
+        self.mint_bump = find_mint_address(program_id).1;
+        self.mint_auth_bump = find_mint_auth_address(program_id).1;
+    }
+}
+impl ReactionKind {
+    pub const COUNT: usize = 4;
+}
+impl StudentIntroReaction {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 32 + 32 + 1 + 8;
+}
+impl ReplierRecord {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 4;
+}
+impl RewardTracker {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 8 + 8;
+
+    /// Counts `amount` against the `daily_cap` of the day of `unix_timestamp`,
+    /// returns the part of it that may still be minted.
+    pub fn take(&mut self, amount: u64, daily_cap: u64, unix_timestamp: i64) -> u64 {
+        let day = unix_timestamp.div_euclid(SECONDS_PER_DAY);
+        if day != self.day {
+            self.day = day;
+            self.rewarded_today = 0;
+        }
+        let allowed = amount.min(daily_cap.saturating_sub(self.rewarded_today));
+        self.rewarded_today += allowed;
+        allowed
+    }
+}
+impl UserProfile {
+    pub fn get_account_size(display_name: &str) -> usize {
+        Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 32 + (4 + display_name.len()) + 8 + 8 + 8 + 8
+    }
+}
+impl IntroMarker {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 32;
+}
+impl ContentReport {
+    pub const SIZE: usize = Self::DISCRIMINATOR.len() + 1 + 1 + 1 + 32 + 32 + 1;
+}
+impl Cohort {
+    pub fn get_account_size(name: &str, params: &CohortParams) -> usize {
+        Self::DISCRIMINATOR.len()
+            + 1
+            + 1
+            + 1
+            + 32
+            + (4 + name.len())
+            + 1
+            + (1 + params.intro_reward.map_or(0, |_| 8))
+            + (1 + params.reply_reward.map_or(0, |_| 8))
+    }
+}
+
+/// Account types owned by the program, each starting with its discriminator
+/// followed by its layout version.
+pub trait ProgramAccount: BorshSerialize + BorshDeserialize + IsInitialized {
+    const DISCRIMINATOR: [u8; 8];
+
+    /// Decodes account data of any known layout version into the latest layout,
+    /// searching bumps that older layouts did not store.
+    fn unpack(data: &[u8], program_id: &Pubkey) -> Result<Self, ProgramError>;
+}
+impl ProgramAccount for StudentIntroAccountState {
+    const DISCRIMINATOR: [u8; 8] = *b"intro\0\0\0";
+
+    fn unpack(data: &[u8], program_id: &Pubkey) -> Result<Self, ProgramError> {
+        let mut state = match data.get(Self::DISCRIMINATOR.len()) {
+            Some(&ACCOUNT_VERSION) => return unpack_as::<Self, Self>(data),
+            Some(12) => return unpack_as::<v12::StudentIntroAccountState, Self>(data),
+            Some(11) => return unpack_as::<v11::StudentIntroAccountState, Self>(data),
+            Some(10) => return unpack_as::<v10::StudentIntroAccountState, Self>(data),
+            Some(8..=9) => return unpack_as::<v9::StudentIntroAccountState, Self>(data),
+            Some(5..=7) => return unpack_as::<v7::StudentIntroAccountState, Self>(data),
+            Some(3) | Some(4) => return unpack_as::<v3::StudentIntroAccountState, Self>(data),
+            Some(2) => unpack_as::<v2::StudentIntroAccountState, Self>(data)?,
+            Some(1) => unpack_as::<v1::StudentIntroAccountState, Self>(data)?,
+            _ => return Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        };
+        state.find_bumps(program_id);
+        Ok(state)
+    }
+}
+impl ProgramAccount for StudentIntroReplyCounter {
+    const DISCRIMINATOR: [u8; 8] = *b"counter\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // counters from version 2 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(2..=12) => {
+                unpack_as::<Self, Self>(data).map(|counter| Self {
+                    version: ACCOUNT_VERSION,
+                    ..counter
+                })
+            }
+            Some(1) => unpack_as::<v1::StudentIntroReplyCounter, Self>(data),
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for StudentIntroReply {
+    const DISCRIMINATOR: [u8; 8] = *b"reply\0\0\0";
+
+    fn unpack(data: &[u8], program_id: &Pubkey) -> Result<Self, ProgramError> {
+        let mut state = match data.get(Self::DISCRIMINATOR.len()) {
+            Some(&ACCOUNT_VERSION) => return unpack_as::<Self, Self>(data),
+            // replies from version 12 on only differ in their version byte
+            Some(12) => {
+                return unpack_as::<Self, Self>(data).map(|reply| Self {
+                    version: ACCOUNT_VERSION,
+                    ..reply
+                })
+            }
+            Some(11) => return unpack_as::<v11::StudentIntroReply, Self>(data),
+            Some(10) => return unpack_as::<v10::StudentIntroReply, Self>(data),
+            Some(5..=9) => return unpack_as::<v9::StudentIntroReply, Self>(data),
+            Some(4) => return unpack_as::<v4::StudentIntroReply, Self>(data),
+            Some(3) => unpack_as::<v3::StudentIntroReply, Self>(data)?,
+            Some(2) => unpack_as::<v2::StudentIntroReply, Self>(data)?,
+            Some(1) => unpack_as::<v1::StudentIntroReply, Self>(data)?,
+            _ => return Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        };
+        state.find_bumps(program_id);
+        Ok(state)
+    }
+}
+impl ProgramAccount for ProgramConfig {
+    const DISCRIMINATOR: [u8; 8] = *b"config\0\0";
+
+    fn unpack(data: &[u8], program_id: &Pubkey) -> Result<Self, ProgramError> {
+        let mut state = match data.get(Self::DISCRIMINATOR.len()) {
+            Some(&ACCOUNT_VERSION) => return unpack_as::<Self, Self>(data),
+            // configs from version 11 on only differ in their version byte
+            Some(11..=12) => {
+                return unpack_as::<Self, Self>(data).map(|config| Self {
+                    version: ACCOUNT_VERSION,
+                    ..config
+                })
+            }
+            Some(10) => return unpack_as::<v10::ProgramConfig, Self>(data),
+            Some(9) => return unpack_as::<v9::ProgramConfig, Self>(data),
+            Some(7..=8) => return unpack_as::<v8::ProgramConfig, Self>(data),
+            Some(6) => return unpack_as::<v6::ProgramConfig, Self>(data),
+            Some(3..=5) => return unpack_as::<v5::ProgramConfig, Self>(data),
+            Some(2) => unpack_as::<v2::ProgramConfig, Self>(data)?,
+            Some(1) => unpack_as::<v1::ProgramConfig, Self>(data)?,
+            _ => return Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        };
+        state.find_bumps(program_id);
+        Ok(state)
+    }
+}
+
+impl ProgramAccount for StudentIntroReaction {
+    const DISCRIMINATOR: [u8; 8] = *b"reaction";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            Some(&ACCOUNT_VERSION) => unpack_as::<Self, Self>(data),
+            Some(5..=12) => unpack_as::<v12::StudentIntroReaction, Self>(data),
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for ReplierRecord {
+    const DISCRIMINATOR: [u8; 8] = *b"replier\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // records from version 6 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(6..=12) => {
+                unpack_as::<Self, Self>(data).map(|record| Self {
+                    version: ACCOUNT_VERSION,
+                    ..record
+                })
+            }
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for RewardTracker {
+    const DISCRIMINATOR: [u8; 8] = *b"rewards\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // trackers from version 6 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(6..=12) => {
+                unpack_as::<Self, Self>(data).map(|tracker| Self {
+                    version: ACCOUNT_VERSION,
+                    ..tracker
+                })
+            }
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for UserProfile {
+    const DISCRIMINATOR: [u8; 8] = *b"profile\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // profiles from version 6 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(6..=12) => {
+                unpack_as::<Self, Self>(data).map(|profile| Self {
+                    version: ACCOUNT_VERSION,
+                    ..profile
+                })
+            }
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for IntroMarker {
+    const DISCRIMINATOR: [u8; 8] = *b"marker\0\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // markers from version 7 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(7..=12) => {
+                unpack_as::<Self, Self>(data).map(|marker| Self {
+                    version: ACCOUNT_VERSION,
+                    ..marker
+                })
+            }
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for ContentReport {
+    const DISCRIMINATOR: [u8; 8] = *b"report\0\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // reports from version 11 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(11..=12) => {
+                unpack_as::<Self, Self>(data).map(|report| Self {
+                    version: ACCOUNT_VERSION,
+                    ..report
+                })
+            }
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+impl ProgramAccount for Cohort {
+    const DISCRIMINATOR: [u8; 8] = *b"cohort\0\0";
+
+    fn unpack(data: &[u8], _program_id: &Pubkey) -> Result<Self, ProgramError> {
+        match data.get(Self::DISCRIMINATOR.len()) {
+            // cohorts from version 12 on only differ in their version byte
+            Some(&ACCOUNT_VERSION) | Some(12) => unpack_as::<Self, Self>(data).map(|cohort| Self {
+                version: ACCOUNT_VERSION,
+                ..cohort
+            }),
+            _ => Err(StudentIntroError::UnsupportedAccountVersion.into()),
+        }
+    }
+}
+
+/// Decodes `data` written with layout `V`, exactly with no trailing bytes, and
+/// converts it to `T`.
+fn unpack_as<V: BorshDeserialize, T: From<V>>(data: &[u8]) -> Result<T, ProgramError> {
+    V::try_from_slice(data)
+        .map(T::from)
+        .map_err(|_| StudentIntroError::CorruptAccountData.into())
+}
+
+/// Deserializes an account of type `T` after checking that it is owned by the
+/// program and carries `T`'s discriminator. Older layout versions are converted
+/// to the latest one, the data must decode exactly and the account must be
+/// initialized.
+pub fn load_account<T: ProgramAccount>(
+    account: &AccountInfo,
+    program_id: &Pubkey,
+) -> Result<T, ProgramError> {
+    if account.owner != program_id {
+        msg!("account {} not owned by program", account.key);
+        return Err(ProgramError::IllegalOwner);
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() < T::DISCRIMINATOR.len() || data[..T::DISCRIMINATOR.len()] != T::DISCRIMINATOR {
+        msg!("account {} has an unexpected discriminator", account.key);
+        return Err(StudentIntroError::InvalidDiscriminator.into());
+    }
+    let state = match T::unpack(&data, program_id) {
+        Ok(state) => state,
+        Err(e) => {
+            msg!("account {} data could not be decoded", account.key);
+            return Err(e);
+        }
+    };
+    if !state.is_initialized() {
+        msg!("account {} not initialized", account.key);
+        return Err(StudentIntroError::UninitializedAccount.into());
+    }
+    Ok(state)
+}
+
+/// Layouts with String discriminators, only read by `MigrateAccount`.
+pub mod v0 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: String,
+        pub is_initialized: bool,
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReplyCounter {
+        pub discriminator: String,
+        pub is_initialized: bool,
+        pub counter: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: String,
+        pub is_initialized: bool,
+        pub intro: Pubkey,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: String,
+        pub is_initialized: bool,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+    }
+}
+
+/// Layouts with fixed discriminators but no version byte.
+pub mod v1 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub is_initialized: bool,
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReplyCounter {
+        pub discriminator: [u8; 8],
+        pub is_initialized: bool,
+        pub counter: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub is_initialized: bool,
+        pub intro: Pubkey,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub is_initialized: bool,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+    }
+}
+
+/// Layouts with a version byte but no PDA bumps.
+pub mod v2 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub intro: Pubkey,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+    }
+}
+
+/// Layouts with PDA bumps but without threading, intros are also written in
+/// this layout by version 4.
+pub mod v3 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub intro: Pubkey,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+}
+
+/// Reply layout with threading but without reaction counts.
+pub mod v4 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub intro: Pubkey,
+        pub parent_reply: Option<Pubkey>,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+}
+
+/// Config layout with PDA bumps but without anti-abuse rules, written by
+/// versions 3 to 5.
+pub mod v5 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub mint_bump: u8,
+        pub mint_auth_bump: u8,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+    }
+}
+
+/// Config layout with anti-abuse rules but without the one intro per wallet
+/// mode, written by version 6.
+pub mod v6 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub mint_bump: u8,
+        pub mint_auth_bump: u8,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+        pub reward_self_replies: bool,
+        pub max_rewarded_replies_per_intro: u32,
+        pub daily_reward_cap: u64,
+    }
+}
+
+/// Intro layout with reaction counts but without an origin, written by
+/// versions 5 to 7.
+pub mod v7 {
+    use crate::state::ReactionKind;
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+    }
+}
+
+/// Config layout without text limits, written by versions 7 and 8.
+pub mod v8 {
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub mint_bump: u8,
+        pub mint_auth_bump: u8,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+        pub reward_self_replies: bool,
+        pub max_rewarded_replies_per_intro: u32,
+        pub daily_reward_cap: u64,
+        pub one_intro_per_wallet: bool,
+    }
+}
+
+/// Layouts without moderation, intros written by versions 8 and 9, replies by
+/// versions 5 to 9 and configs by version 9.
+pub mod v9 {
+    use crate::state::ReactionKind;
+    use crate::validation::TextLimits;
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+        pub origin: Option<Pubkey>,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub intro: Pubkey,
+        pub parent_reply: Option<Pubkey>,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub mint_bump: u8,
+        pub mint_auth_bump: u8,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+        pub reward_self_replies: bool,
+        pub max_rewarded_replies_per_intro: u32,
+        pub daily_reward_cap: u64,
+        pub one_intro_per_wallet: bool,
+        pub name_limits: TextLimits,
+        pub msg_limits: TextLimits,
+        pub reply_limits: TextLimits,
+    }
+}
+
+/// Layouts without reports, written by version 10.
+pub mod v10 {
+    use crate::state::{ReactionKind, MAX_MODERATORS};
+    use crate::validation::TextLimits;
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub hidden: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+        pub origin: Option<Pubkey>,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub hidden: bool,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub intro: Pubkey,
+        pub parent_reply: Option<Pubkey>,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct ProgramConfig {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub mint_bump: u8,
+        pub mint_auth_bump: u8,
+        pub admin: Pubkey,
+        pub intro_reward: u64,
+        pub reply_reward: u64,
+        pub max_intro_size: u32,
+        pub mint_decimals: u8,
+        pub reward_self_replies: bool,
+        pub max_rewarded_replies_per_intro: u32,
+        pub daily_reward_cap: u64,
+        pub one_intro_per_wallet: bool,
+        pub name_limits: TextLimits,
+        pub msg_limits: TextLimits,
+        pub reply_limits: TextLimits,
+        pub moderators: [Pubkey; MAX_MODERATORS],
+    }
+}
+
+/// Layouts without cohorts, intros and replies written by version 11.
+pub mod v11 {
+    use crate::state::ReactionKind;
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub hidden: bool,
+        pub report_count: u32,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+        pub origin: Option<Pubkey>,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReply {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub hidden: bool,
+        pub report_count: u32,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub intro: Pubkey,
+        pub parent_reply: Option<Pubkey>,
+        pub replier: Pubkey,
+        pub reply: String,
+        pub count: u64,
+    }
+}
+
+/// Layouts without creation slots, intros written by version 12 and reactions
+/// by versions 5 to 12.
+pub mod v12 {
+    use crate::state::ReactionKind;
+    use borsh::BorshDeserialize;
+    use solana_program::pubkey::Pubkey;
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroAccountState {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub hidden: bool,
+        pub report_count: u32,
+        pub bump: u8,
+        pub counter_bump: u8,
+        pub reaction_counts: [u64; ReactionKind::COUNT],
+        pub identity: Pubkey,
+        pub name: String,
+        pub msg: String,
+        pub origin: Option<Pubkey>,
+        pub cohort: Option<Pubkey>,
+    }
+
+    #[derive(BorshDeserialize)]
+    pub struct StudentIntroReaction {
+        pub discriminator: [u8; 8],
+        pub version: u8,
+        pub is_initialized: bool,
+        pub bump: u8,
+        pub target: Pubkey,
+        pub user: Pubkey,
+        pub kind: ReactionKind,
+    }
+}
+
+// Conversions from older layouts leave PDA bumps zeroed, `find_bumps` fills
+// them in. Intros and replies start without reports, on the global board, and
+// intros and reactions without a creation slot. Configs get the default
+// anti-abuse rules, text limits, report threshold and no moderators.
+impl From<v0::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v0::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: None,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+impl From<v0::StudentIntroReplyCounter> for StudentIntroReplyCounter {
+    fn from(old: v0::StudentIntroReplyCounter) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            counter: old.counter,
+        }
+    }
+}
+impl From<v0::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v0::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: old.intro,
+            parent_reply: None,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+impl From<v0::ProgramConfig> for ProgramConfig {
+    fn from(old: v0::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: 0,
+            mint_bump: 0,
+            mint_auth_bump: 0,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: defaults.reward_self_replies,
+            max_rewarded_replies_per_intro: defaults.max_rewarded_replies_per_intro,
+            daily_reward_cap: defaults.daily_reward_cap,
+            one_intro_per_wallet: defaults.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+impl From<v1::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v1::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: None,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+impl From<v1::StudentIntroReplyCounter> for StudentIntroReplyCounter {
+    fn from(old: v1::StudentIntroReplyCounter) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            counter: old.counter,
+        }
+    }
+}
+impl From<v1::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v1::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: old.intro,
+            parent_reply: None,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+impl From<v1::ProgramConfig> for ProgramConfig {
+    fn from(old: v1::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: 0,
+            mint_bump: 0,
+            mint_auth_bump: 0,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: defaults.reward_self_replies,
+            max_rewarded_replies_per_intro: defaults.max_rewarded_replies_per_intro,
+            daily_reward_cap: defaults.daily_reward_cap,
+            one_intro_per_wallet: defaults.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+impl From<v2::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v2::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: None,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+impl From<v2::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v2::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: 0,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: old.intro,
+            parent_reply: None,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+impl From<v2::ProgramConfig> for ProgramConfig {
+    fn from(old: v2::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: 0,
+            mint_bump: 0,
+            mint_auth_bump: 0,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: defaults.reward_self_replies,
+            max_rewarded_replies_per_intro: defaults.max_rewarded_replies_per_intro,
+            daily_reward_cap: defaults.daily_reward_cap,
+            one_intro_per_wallet: defaults.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+
+impl From<v3::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v3::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: 0,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: old.intro,
+            parent_reply: None,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+
+impl From<v3::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v3::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: [0; ReactionKind::COUNT],
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: None,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+impl From<v4::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v4::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: old.intro,
+            parent_reply: old.parent_reply,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+
+impl From<v5::ProgramConfig> for ProgramConfig {
+    fn from(old: v5::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            mint_auth_bump: old.mint_auth_bump,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: defaults.reward_self_replies,
+            max_rewarded_replies_per_intro: defaults.max_rewarded_replies_per_intro,
+            daily_reward_cap: defaults.daily_reward_cap,
+            one_intro_per_wallet: defaults.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+
+impl From<v6::ProgramConfig> for ProgramConfig {
+    fn from(old: v6::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            mint_auth_bump: old.mint_auth_bump,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: old.reward_self_replies,
+            max_rewarded_replies_per_intro: old.max_rewarded_replies_per_intro,
+            daily_reward_cap: old.daily_reward_cap,
+            one_intro_per_wallet: defaults.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+
+impl From<v8::ProgramConfig> for ProgramConfig {
+    fn from(old: v8::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            mint_auth_bump: old.mint_auth_bump,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: old.reward_self_replies,
+            max_rewarded_replies_per_intro: old.max_rewarded_replies_per_intro,
+            daily_reward_cap: old.daily_reward_cap,
+            one_intro_per_wallet: old.one_intro_per_wallet,
+            name_limits: defaults.name_limits,
+            msg_limits: defaults.msg_limits,
+            reply_limits: defaults.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+
+impl From<v7::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v7::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: None,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+
+impl From<v9::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v9::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: old.origin,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+
+impl From<v9::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v9::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: false,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            intro: old.intro,
+            parent_reply: old.parent_reply,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+
+impl From<v9::ProgramConfig> for ProgramConfig {
+    fn from(old: v9::ProgramConfig) -> Self {
+        let defaults = ConfigParams::default();
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            mint_auth_bump: old.mint_auth_bump,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: old.reward_self_replies,
+            max_rewarded_replies_per_intro: old.max_rewarded_replies_per_intro,
+            daily_reward_cap: old.daily_reward_cap,
+            one_intro_per_wallet: old.one_intro_per_wallet,
+            name_limits: old.name_limits,
+            msg_limits: old.msg_limits,
+            reply_limits: old.reply_limits,
+            moderators: defaults.moderators,
+            report_threshold: defaults.report_threshold,
+        }
+    }
+}
+
+impl From<v12::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v12::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: old.hidden,
+            report_count: old.report_count,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: old.origin,
+            cohort: old.cohort,
+            created_slot: 0,
+        }
+    }
+}
+
+impl From<v12::StudentIntroReaction> for StudentIntroReaction {
+    fn from(old: v12::StudentIntroReaction) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            target: old.target,
+            user: old.user,
+            kind: old.kind,
+            target_slot: 0,
+        }
+    }
+}
+
+impl From<v11::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v11::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: old.hidden,
+            report_count: old.report_count,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: old.origin,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+
+impl From<v11::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v11::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: old.hidden,
+            report_count: old.report_count,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            intro: old.intro,
+            parent_reply: old.parent_reply,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+
+impl From<v10::StudentIntroAccountState> for StudentIntroAccountState {
+    fn from(old: v10::StudentIntroAccountState) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: old.hidden,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            identity: old.identity,
+            name: old.name,
+            msg: old.msg,
+            origin: old.origin,
+            cohort: None,
+            created_slot: 0,
+        }
+    }
+}
+
+impl From<v10::StudentIntroReply> for StudentIntroReply {
+    fn from(old: v10::StudentIntroReply) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            hidden: old.hidden,
+            report_count: 0,
+            bump: old.bump,
+            counter_bump: old.counter_bump,
+            reaction_counts: old.reaction_counts,
+            intro: old.intro,
+            parent_reply: old.parent_reply,
+            replier: old.replier,
+            reply: old.reply,
+            count: old.count,
+            cohort: None,
+        }
+    }
+}
+
+impl From<v10::ProgramConfig> for ProgramConfig {
+    fn from(old: v10::ProgramConfig) -> Self {
+        Self {
+            discriminator: Self::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: old.is_initialized,
+            bump: old.bump,
+            mint_bump: old.mint_bump,
+            mint_auth_bump: old.mint_auth_bump,
+            admin: old.admin,
+            intro_reward: old.intro_reward,
+            reply_reward: old.reply_reward,
+            max_intro_size: old.max_intro_size,
+            mint_decimals: old.mint_decimals,
+            reward_self_replies: old.reward_self_replies,
+            max_rewarded_replies_per_intro: old.max_rewarded_replies_per_intro,
+            daily_reward_cap: old.daily_reward_cap,
+            one_intro_per_wallet: old.one_intro_per_wallet,
+            name_limits: old.name_limits,
+            msg_limits: old.msg_limits,
+            reply_limits: old.reply_limits,
+            moderators: old.moderators,
+            report_threshold: ConfigParams::default().report_threshold,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_sizes_match_serialized_len() {
+        let (name, msg) = ("Naruto".to_owned(), "Developing solana jutsu".to_owned());
+        let intro = StudentIntroAccountState {
+            discriminator: StudentIntroAccountState::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            hidden: false,
+            report_count: 0,
+            bump: 255,
+            counter_bump: 255,
+            reaction_counts: [0; ReactionKind::COUNT],
+            identity: Pubkey::new_unique(),
+            name: name.clone(),
+            msg: msg.clone(),
+            origin: Some(Pubkey::new_unique()),
+            cohort: Some(Pubkey::new_unique()),
+            created_slot: 1,
+        };
+        assert_eq!(
+            intro.try_to_vec().unwrap().len(),
+            StudentIntroAccountState::get_account_size(name, msg, intro.origin, intro.cohort)
+        );
+
+        let counter = StudentIntroReplyCounter {
+            discriminator: StudentIntroReplyCounter::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            counter: 0,
+        };
+        assert_eq!(
+            counter.try_to_vec().unwrap().len(),
+            StudentIntroReplyCounter::SIZE
+        );
+
+        let reply_text = "All the best Naruto".to_owned();
+        let reply = StudentIntroReply {
+            discriminator: StudentIntroReply::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            hidden: false,
+            report_count: 0,
+            bump: 255,
+            counter_bump: 255,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: Pubkey::new_unique(),
+            parent_reply: None,
+            replier: Pubkey::new_unique(),
+            reply: reply_text.clone(),
+            count: 0,
+            cohort: None,
+        };
+        assert_eq!(
+            reply.try_to_vec().unwrap().len(),
+            StudentIntroReply::get_account_size(reply_text.clone(), None, None)
+        );
+        let parent_reply = Some(Pubkey::new_unique());
+        let cohort = Some(Pubkey::new_unique());
+        let child = StudentIntroReply {
+            parent_reply,
+            reply: reply_text.clone(),
+            cohort,
+            ..reply
+        };
+        assert_eq!(
+            child.try_to_vec().unwrap().len(),
+            StudentIntroReply::get_account_size(reply_text, parent_reply, cohort)
+        );
+
+        let config = ProgramConfig {
+            discriminator: ProgramConfig::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            mint_bump: 255,
+            mint_auth_bump: 255,
+            admin: Pubkey::new_unique(),
+            intro_reward: 10,
+            reply_reward: 5,
+            max_intro_size: 1000,
+            mint_decimals: 9,
+            reward_self_replies: false,
+            max_rewarded_replies_per_intro: 3,
+            daily_reward_cap: 50,
+            one_intro_per_wallet: true,
+            name_limits: TextLimits::new(1, 32),
+            msg_limits: TextLimits::new(1, 1000),
+            reply_limits: TextLimits::new(1, 500),
+            moderators: [Pubkey::new_unique(); MAX_MODERATORS],
+            report_threshold: 3,
+        };
+        assert_eq!(config.try_to_vec().unwrap().len(), ProgramConfig::SIZE);
+
+        let reaction = StudentIntroReaction {
+            discriminator: StudentIntroReaction::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            target: Pubkey::new_unique(),
+            user: Pubkey::new_unique(),
+            kind: ReactionKind::Like,
+            target_slot: 1,
+        };
+        assert_eq!(
+            reaction.try_to_vec().unwrap().len(),
+            StudentIntroReaction::SIZE
+        );
+
+        let record = ReplierRecord {
+            discriminator: ReplierRecord::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            rewarded_replies: 0,
+        };
+        assert_eq!(record.try_to_vec().unwrap().len(), ReplierRecord::SIZE);
+
+        let tracker = RewardTracker {
+            discriminator: RewardTracker::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            day: 0,
+            rewarded_today: 0,
+        };
+        assert_eq!(tracker.try_to_vec().unwrap().len(), RewardTracker::SIZE);
+
+        let profile = UserProfile {
+            discriminator: UserProfile::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            wallet: Pubkey::new_unique(),
+            display_name: "Naruto".to_owned(),
+            total_intros: 1,
+            total_replies: 2,
+            total_tokens_earned: 3,
+            joined_at: 1_700_000_000,
+        };
+        assert_eq!(
+            profile.try_to_vec().unwrap().len(),
+            UserProfile::get_account_size(&profile.display_name)
+        );
+
+        let marker = IntroMarker {
+            discriminator: IntroMarker::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            intro: Pubkey::new_unique(),
+        };
+        assert_eq!(marker.try_to_vec().unwrap().len(), IntroMarker::SIZE);
+
+        let report = ContentReport {
+            discriminator: ContentReport::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            target: Pubkey::new_unique(),
+            reporter: Pubkey::new_unique(),
+            reason: ReportReason::Spam,
+        };
+        assert_eq!(report.try_to_vec().unwrap().len(), ContentReport::SIZE);
+
+        let params = CohortParams {
+            open: true,
+            intro_reward: Some(20),
+            reply_reward: None,
+        };
+        let cohort = Cohort {
+            discriminator: Cohort::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            admin: Pubkey::new_unique(),
+            name: "Class of 2026".to_owned(),
+            open: params.open,
+            intro_reward: params.intro_reward,
+            reply_reward: params.reply_reward,
+        };
+        assert_eq!(
+            cohort.try_to_vec().unwrap().len(),
+            Cohort::get_account_size(&cohort.name, &params)
+        );
+    }
+
+    #[test]
+    fn test_load_account_checks() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let reply = StudentIntroReply {
+            discriminator: StudentIntroReply::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            hidden: false,
+            report_count: 0,
+            bump: 255,
+            counter_bump: 255,
+            reaction_counts: [0; ReactionKind::COUNT],
+            intro: Pubkey::new_unique(),
+            parent_reply: None,
+            replier: Pubkey::new_unique(),
+            reply: "All the best".to_owned(),
+            count: 0,
+            cohort: None,
+        };
+        let mut data = reply.try_to_vec().unwrap();
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        assert!(load_account::<StudentIntroReply>(&account, &program_id).is_ok());
+        // a reply passed where an intro is expected
+        assert_eq!(
+            load_account::<StudentIntroAccountState>(&account, &program_id).err(),
+            Some(StudentIntroError::InvalidDiscriminator.into())
+        );
+        assert_eq!(
+            load_account::<StudentIntroReply>(&account, &Pubkey::new_unique()).err(),
+            Some(ProgramError::IllegalOwner)
+        );
+
+        let mut lamports = 0;
+        let mut data = reply.try_to_vec().unwrap();
+        data.push(0);
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        assert_eq!(
+            load_account::<StudentIntroReply>(&account, &program_id).err(),
+            Some(StudentIntroError::CorruptAccountData.into())
+        );
+    }
+
+    #[test]
+    fn test_load_account_reads_v1_layout() {
+        let program_id = Pubkey::new_unique();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let identity = Pubkey::new_unique();
+        // discriminator, is_initialized, identity, name, msg
+        let mut data = (
+            StudentIntroAccountState::DISCRIMINATOR,
+            true,
+            identity,
+            "Naruto".to_owned(),
+            "Hi".to_owned(),
+        )
+            .try_to_vec()
+            .unwrap();
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        let intro = load_account::<StudentIntroAccountState>(&account, &program_id).unwrap();
+        assert_eq!(intro.version, ACCOUNT_VERSION);
+        let (intro_address, bump) = find_intro_address(&program_id, &identity, "Naruto");
+        assert_eq!(intro.bump, bump);
+        assert_eq!(
+            intro.counter_bump,
+            find_counter_address(&program_id, &intro_address).1
+        );
+        assert_eq!(intro.identity, identity);
+        assert_eq!(intro.name, "Naruto");
+        assert_eq!(intro.msg, "Hi");
+    }
+
+    #[test]
+    fn test_reward_tracker_daily_cap() {
+        let mut tracker = RewardTracker {
+            discriminator: RewardTracker::DISCRIMINATOR,
+            version: ACCOUNT_VERSION,
+            is_initialized: true,
+            bump: 255,
+            day: 0,
+            rewarded_today: 0,
+        };
+        let noon = 19_000 * SECONDS_PER_DAY + SECONDS_PER_DAY / 2;
+        assert_eq!(tracker.take(10, 25, noon), 10);
+        assert_eq!(tracker.take(10, 25, noon + 60), 10);
+        // only what is left of the cap is minted
+        assert_eq!(tracker.take(10, 25, noon + 120), 5);
+        assert_eq!(tracker.take(10, 25, noon + 180), 0);
+        // the cap resets on the next day
+        assert_eq!(tracker.take(10, 25, noon + SECONDS_PER_DAY), 10);
+        assert_eq!(tracker.rewarded_today, 10);
     }
 }
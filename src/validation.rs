@@ -0,0 +1,142 @@
+use crate::error::StudentIntroError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::pubkey::MAX_SEED_LEN;
+use unicode_normalization::is_nfc;
+
+/// Invisible characters that can disguise text, rejected alongside control
+/// characters: zero width characters, bidi controls and the byte order mark.
+pub const BANNED_CHARS: [char; 15] = [
+    '\u{200B}', '\u{200C}', '\u{200D}', '\u{200E}', '\u{200F}', '\u{202A}', '\u{202B}', '\u{202C}',
+    '\u{202D}', '\u{202E}', '\u{2066}', '\u{2067}', '\u{2068}', '\u{2069}', '\u{FEFF}',
+];
+
+/// Bounds on the length of a text field, in characters.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLimits {
+    pub min_len: u32,
+    pub max_len: u32,
+}
+
+impl TextLimits {
+    pub const fn new(min_len: u32, max_len: u32) -> Self {
+        Self { min_len, max_len }
+    }
+}
+
+/// Whether `c` may not appear in names, messages or replies.
+pub fn is_banned(c: char) -> bool {
+    c.is_control() || BANNED_CHARS.contains(&c)
+}
+
+/// Checks a name, message or reply. Text is stored as sent, so it must already
+/// be trimmed and NFC normalized, names being part of intro PDA seeds.
+pub fn validate_text(text: &str, limits: &TextLimits) -> Result<(), StudentIntroError> {
+    let trimmed = text.trim();
+    if trimmed.is_empty() {
+        return Err(StudentIntroError::EmptyText);
+    }
+    if trimmed.len() != text.len() {
+        return Err(StudentIntroError::UntrimmedText);
+    }
+    if text.chars().any(is_banned) {
+        return Err(StudentIntroError::BannedCharacter);
+    }
+    if !is_nfc(text) {
+        return Err(StudentIntroError::UnnormalizedText);
+    }
+    let len = text.chars().count();
+    if len < limits.min_len as usize {
+        return Err(StudentIntroError::TextTooShort);
+    }
+    if len > limits.max_len as usize {
+        return Err(StudentIntroError::TextTooLong);
+    }
+    Ok(())
+}
+
+/// Checks an intro or cohort name, which as a PDA seed may not exceed
+/// `MAX_SEED_LEN` bytes however few characters it has.
+pub fn validate_name(text: &str, limits: &TextLimits) -> Result<(), StudentIntroError> {
+    validate_text(text, limits)?;
+    if text.len() > MAX_SEED_LEN {
+        return Err(StudentIntroError::SeedTooLong);
+    }
+    Ok(())
+}
+
+/// Checks the name limits of a config, names longer than a seed never fit.
+pub fn validate_name_limits(limits: &TextLimits) -> Result<(), StudentIntroError> {
+    if limits.max_len as usize > MAX_SEED_LEN {
+        return Err(StudentIntroError::InvalidNameLimits);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_text() {
+        let limits = TextLimits::new(2, 6);
+        assert_eq!(validate_text("Naruto", &limits), Ok(()));
+        // lengths count characters, not bytes
+        assert_eq!(validate_text("Café", &limits), Ok(()));
+        assert_eq!(
+            validate_text("", &limits),
+            Err(StudentIntroError::EmptyText)
+        );
+        assert_eq!(
+            validate_text(" \t", &limits),
+            Err(StudentIntroError::EmptyText)
+        );
+        assert_eq!(
+            validate_text(" Hina", &limits),
+            Err(StudentIntroError::UntrimmedText)
+        );
+        assert_eq!(
+            validate_text("Hi\nna", &limits),
+            Err(StudentIntroError::BannedCharacter)
+        );
+        assert_eq!(
+            validate_text("Hi\u{200B}na", &limits),
+            Err(StudentIntroError::BannedCharacter)
+        );
+        assert_eq!(
+            validate_text("Cafe\u{301}", &limits),
+            Err(StudentIntroError::UnnormalizedText)
+        );
+        assert_eq!(
+            validate_text("N", &limits),
+            Err(StudentIntroError::TextTooShort)
+        );
+        assert_eq!(
+            validate_text("Naruto Uzumaki", &limits),
+            Err(StudentIntroError::TextTooLong)
+        );
+    }
+
+    #[test]
+    fn test_validate_name() {
+        let limits = TextLimits::new(1, 32);
+        assert_eq!(validate_name(&"a".repeat(32), &limits), Ok(()));
+        // 32 characters but 96 bytes
+        assert_eq!(
+            validate_name(&"う".repeat(32), &limits),
+            Err(StudentIntroError::SeedTooLong)
+        );
+        assert_eq!(
+            validate_name(&"a".repeat(33), &limits),
+            Err(StudentIntroError::TextTooLong)
+        );
+        assert_eq!(
+            validate_name(&"a".repeat(33), &TextLimits::new(1, 40)),
+            Err(StudentIntroError::SeedTooLong)
+        );
+        assert_eq!(validate_name_limits(&limits), Ok(()));
+        assert_eq!(
+            validate_name_limits(&TextLimits::new(1, 33)),
+            Err(StudentIntroError::InvalidNameLimits)
+        );
+    }
+}